@@ -1 +1,3 @@
+pub mod endpoint;
 pub mod gke_client;
+pub mod retry;
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use googapis::CERTIFICATES;
+use std::fs;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+
+pub const DEFAULT_GKE_ENDPOINT: &str = "https://container.googleapis.com";
+pub const DEFAULT_COMPUTE_ENDPOINT: &str = "https://compute.googleapis.com/compute/v1";
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Endpoint {
+    pub url: String,
+    pub plaintext: bool,
+    pub ca_certificate: Option<String>,
+}
+
+impl Default for Endpoint {
+    fn default() -> Endpoint {
+        Endpoint {
+            url: format!("{}", DEFAULT_GKE_ENDPOINT),
+            plaintext: false,
+            ca_certificate: None,
+        }
+    }
+}
+
+impl Endpoint {
+    pub fn new(url: String, plaintext: bool, ca_certificate: Option<String>) -> Endpoint {
+        Endpoint {
+            url,
+            plaintext,
+            ca_certificate,
+        }
+    }
+
+    // plaintext endpoint is used for emulators and fake servers, so it is not authenticated.
+    pub fn is_authenticated(&self) -> bool {
+        !self.plaintext
+    }
+
+    pub async fn connect(&self) -> Result<Channel> {
+        log::debug!("connect to {}", self.url);
+        let mut endpoint = Channel::from_shared(self.url.clone())
+            .with_context(|| format!("invalid endpoint: {}", self.url))?;
+        if !self.plaintext {
+            endpoint = endpoint.tls_config(self.tls_config()?)?;
+        }
+
+        endpoint.connect().await.map_err(|e| {
+            let msg = format!("{}", e);
+            anyhow::Error::new(e).context(msg)
+        })
+    }
+
+    // rest_url is the url of a resource of GKE REST API, which is served on the same endpoint as
+    // gRPC, like "https://container.googleapis.com/v1/projects/P/locations/L/clusters/C".
+    pub fn rest_url(&self, name: &str) -> String {
+        format!("{}/v1/{}", self.url.trim_end_matches('/'), name)
+    }
+
+    // http_client trusts the same ca certificate as gRPC channel.
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+        if let (false, Some(path)) = (self.plaintext, &self.ca_certificate) {
+            let certificate = reqwest::Certificate::from_pem(
+                &fs::read(path)
+                    .with_context(|| format!("failed to open ca certificate: {}", path))?,
+            )
+            .with_context(|| format!("invalid ca certificate: {}", path))?;
+            builder = builder
+                .tls_built_in_root_certs(false)
+                .add_root_certificate(certificate);
+        }
+        Ok(builder.build()?)
+    }
+
+    fn tls_config(&self) -> Result<ClientTlsConfig> {
+        let certificate = match &self.ca_certificate {
+            Some(path) => Certificate::from_pem(
+                fs::read(path)
+                    .with_context(|| format!("failed to open ca certificate: {}", path))?,
+            ),
+            None => Certificate::from_pem(CERTIFICATES),
+        };
+        Ok(ClientTlsConfig::new().ca_certificate(certificate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::endpoint::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(Endpoint::default(), true)]
+    #[case(
+        Endpoint::new(format!("https://gke.internal.example.com"), false, None),
+        true
+    )]
+    #[case(Endpoint::new(format!("http://127.0.0.1:8080"), true, None), false)]
+    #[trace]
+    fn test_is_authenticated(#[case] endpoint: Endpoint, #[case] expected: bool) {
+        assert_eq!(endpoint.is_authenticated(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Endpoint::default(),
+        "https://container.googleapis.com/v1/projects/project/locations/location/clusters/cluster"
+    )]
+    #[case(
+        Endpoint::new(format!("http://127.0.0.1:8080/"), true, None),
+        "http://127.0.0.1:8080/v1/projects/project/locations/location/clusters/cluster"
+    )]
+    #[trace]
+    fn test_rest_url(#[case] endpoint: Endpoint, #[case] expected: &str) {
+        assert_eq!(
+            endpoint.rest_url("projects/project/locations/location/clusters/cluster"),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Endpoint::new(format!("not a url"), true, None),
+        format!("invalid endpoint: not a url")
+    )]
+    #[case(
+        Endpoint::new(
+            format!("https://127.0.0.1:8443"),
+            false,
+            Some(format!("/path/to/not_found.pem"))
+        ),
+        format!("failed to open ca certificate: /path/to/not_found.pem")
+    )]
+    #[trace]
+    #[tokio::test]
+    async fn test_connect_error(#[case] endpoint: Endpoint, #[case] expected: String) {
+        match endpoint.connect().await {
+            Ok(_) => panic!("connect should fail"),
+            Err(error) => assert_eq!(format!("{}", error), expected),
+        }
+    }
+}
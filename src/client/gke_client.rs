@@ -1,45 +1,248 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use googapis::{
-    google::container::v1::{
-        cluster, cluster_manager_client::ClusterManagerClient, node_pool, GetClusterRequest,
-        GetNodePoolRequest,
-    },
-    CERTIFICATES,
+use googapis::google::container::v1::{
+    cluster_manager_client::ClusterManagerClient, Cluster, GetClusterRequest, GetNodePoolRequest,
+    ListClustersRequest, ListNodePoolsRequest, NodePool,
 };
 use gouth::Token;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
 use tonic::{
-    metadata::MetadataValue,
-    transport::{Certificate, Channel, ClientTlsConfig},
-    Request,
+    codegen::InterceptedService, metadata::MetadataValue, service::Interceptor, transport::Channel,
+    Code, Request, Status,
 };
 
+use crate::client::endpoint::{Endpoint, DEFAULT_COMPUTE_ENDPOINT};
+use crate::client::retry::{retry, Fetched, RetryPolicy};
+use crate::spec::cluster_status::ClusterStatus;
+use crate::spec::node_pool_status::NodePoolStatus;
+
 #[cfg(test)]
 use mockall::{automock, predicate::*};
 
 #[cfg_attr(test, automock)]
 #[async_trait]
-pub trait GKEClientTrait {
+pub trait GKEClientTrait: Send + Sync {
     async fn fetch_cluster_status(
         &self,
-        project: &String,
-        location: &String,
-        cluster: &String,
-    ) -> Result<cluster::Status>;
+        project: &str,
+        location: &str,
+        cluster: &str,
+    ) -> Result<Fetched<ClusterStatus>>;
+    // fetch_cluster returns the whole cluster, for operators checking other than status.
+    async fn fetch_cluster(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+    ) -> Result<Fetched<Cluster>>;
     async fn fetch_node_pool_status(
         &self,
-        project: &String,
-        location: &String,
-        cluster: &String,
-        node_pool: &String,
-    ) -> Result<node_pool::Status>;
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+    ) -> Result<Fetched<NodePoolStatus>>;
+    // fetch_node_pool returns the whole node pool, for operators checking other than status.
+    async fn fetch_node_pool(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+    ) -> Result<Fetched<NodePool>>;
+    // fetch_node_pool_detail returns fields of node pool which are missing in NodePool of googapis,
+    // from GKE REST API.
+    async fn fetch_node_pool_detail(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+    ) -> Result<Fetched<NodePoolDetail>>;
+    // fetch_instance_group_size returns the live number of instances in an instance group of node
+    // pool, which is given by one of instance_group_urls of the node pool.
+    async fn fetch_instance_group_size(&self, instance_group_url: &str) -> Result<Fetched<u64>>;
+    // location "-" lists clusters in all locations.
+    async fn list_clusters(
+        &self,
+        project: &str,
+        location: &str,
+    ) -> Result<Fetched<Vec<ClusterSummary>>>;
+    async fn list_node_pools(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+    ) -> Result<Fetched<Vec<NodePoolSummary>>>;
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClusterSummary {
+    pub name: String,
+    pub location: String,
+    pub status: ClusterStatus,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NodePoolSummary {
+    pub name: String,
+    pub status: NodePoolStatus,
 }
 
-pub struct GKEClient {}
+// NodePoolDetail is the part of node pool resource of GKE REST API used by yubi.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+pub struct NodePoolDetail {
+    #[serde(default)]
+    pub config: NodeConfigDetail,
+    #[serde(default)]
+    pub autoscaling: AutoscalingDetail,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+pub struct NodeConfigDetail {
+    #[serde(default)]
+    pub spot: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoscalingDetail {
+    // location policy like "BALANCED", which is not returned when autoscaling is disabled.
+    pub location_policy: Option<String>,
+}
+
+type AuthenticatedClient = ClusterManagerClient<InterceptedService<Channel, AuthInterceptor>>;
+
+// GKEClient connects lazily on the first fetch, and the connection is shared by all fetches.
+// sizes of instance groups are not returned by GKE API, so they are fetched from Compute Engine
+// API with the same token, and so are fields of node pool missing in googapis from GKE REST API.
+#[derive(Default)]
+pub struct GKEClient {
+    endpoint: Endpoint,
+    compute_endpoint: Option<String>,
+    retry_policy: RetryPolicy,
+    token: OnceCell<Option<Arc<Token>>>,
+    client: OnceCell<AuthenticatedClient>,
+    rest_client: OnceCell<reqwest::Client>,
+    http_client: reqwest::Client,
+}
 
 impl GKEClient {
-    pub fn new() -> GKEClient {
-        GKEClient {}
+    pub fn new(endpoint: Endpoint, retry_policy: RetryPolicy) -> GKEClient {
+        GKEClient {
+            endpoint,
+            compute_endpoint: None,
+            retry_policy,
+            token: OnceCell::new(),
+            client: OnceCell::new(),
+            rest_client: OnceCell::new(),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    // compute_endpoint is the URL of Compute Engine API up to the version, like
+    // DEFAULT_COMPUTE_ENDPOINT. it is authenticated unless GKE API endpoint is plaintext or
+    // compute_endpoint is not https.
+    pub fn with_compute_endpoint(mut self, compute_endpoint: String) -> GKEClient {
+        self.compute_endpoint = Some(compute_endpoint);
+        self
+    }
+
+    async fn token(&self) -> Result<Option<Arc<Token>>> {
+        let token = self
+            .token
+            .get_or_try_init(|| async {
+                if !self.endpoint.is_authenticated() {
+                    return Ok(None);
+                }
+                let token = Token::new().map_err(|e| {
+                    let msg = format!("{}", e);
+                    anyhow::Error::new(e).context(msg)
+                })?;
+                Ok::<Option<Arc<Token>>, anyhow::Error>(Some(Arc::new(token)))
+            })
+            .await?;
+        Ok(token.clone())
+    }
+
+    async fn client(&self) -> Result<AuthenticatedClient> {
+        let client = self
+            .client
+            .get_or_try_init(|| async {
+                let token = self.token().await?;
+                let channel = self.endpoint.connect().await?;
+
+                Ok::<AuthenticatedClient, anyhow::Error>(ClusterManagerClient::with_interceptor(
+                    channel,
+                    AuthInterceptor::new(token),
+                ))
+            })
+            .await?;
+        Ok(client.clone())
+    }
+
+    async fn rest_client(&self) -> Result<reqwest::Client> {
+        let client = self
+            .rest_client
+            .get_or_try_init(|| async { self.endpoint.http_client() })
+            .await?;
+        Ok(client.clone())
+    }
+
+    // get fetches a resource of REST API with the token of GKE API, and retries like gRPC.
+    // the token is sent only over https, so a plain http endpoint like a fake server never
+    // receives it, even if GKE API endpoint is authenticated.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        http_client: &reqwest::Client,
+        url: &str,
+    ) -> Result<Fetched<T>> {
+        let token = match url.starts_with("https://") {
+            true => self.token().await?,
+            false => None,
+        };
+
+        retry(&self.retry_policy, || {
+            let mut request = http_client.get(url);
+            let token = token.as_ref().map(|token| token.header_value());
+            async move {
+                if let Some(token) = token {
+                    let token = token.map_err(|e| Status::unauthenticated(format!("{}", e)))?;
+                    request = request.header("authorization", token.as_str());
+                }
+                get_json(request).await
+            }
+        })
+        .await
+    }
+}
+
+// AuthInterceptor shares one token, which is refreshed by gouth when it expires.
+#[derive(Clone)]
+struct AuthInterceptor {
+    token: Option<Arc<Token>>,
+}
+
+impl AuthInterceptor {
+    fn new(token: Option<Arc<Token>>) -> AuthInterceptor {
+        AuthInterceptor { token }
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = match &self.token {
+            Some(token) => token
+                .header_value()
+                .map_err(|e| Status::unauthenticated(format!("{}", e)))?,
+            None => return Ok(request),
+        };
+        let meta = MetadataValue::from_str(&token)
+            .map_err(|e| Status::unauthenticated(format!("{}", e)))?;
+        request.metadata_mut().insert("authorization", meta);
+        Ok(request)
     }
 }
 
@@ -47,122 +250,331 @@ impl GKEClient {
 impl GKEClientTrait for GKEClient {
     async fn fetch_cluster_status(
         &self,
-        project: &String,
-        location: &String,
-        cluster: &String,
-    ) -> Result<cluster::Status> {
-        let token = Token::new().map_err(|e| {
-            let msg = format!("{}", e);
-            anyhow::Error::new(e).context(msg)
-        })?;
-
-        let tls_config = ClientTlsConfig::new()
-            .ca_certificate(Certificate::from_pem(CERTIFICATES))
-            .domain_name("container.googleapis.com");
-
-        let channel = Channel::from_static("https://container.googleapis.com")
-            .tls_config(tls_config)?
-            .connect()
-            .await
-            .map_err(|e| {
-                let msg = format!("{}", e);
-                anyhow::Error::new(e).context(msg)
-            })?;
-
-        let mut client =
-            ClusterManagerClient::with_interceptor(channel, move |mut request: Request<()>| {
-                let token = &*token.header_value().unwrap();
-                let meta = MetadataValue::from_str(token).unwrap();
-                request.metadata_mut().insert("authorization", meta);
-                Ok(request)
-            });
+        project: &str,
+        location: &str,
+        cluster: &str,
+    ) -> Result<Fetched<ClusterStatus>> {
+        let fetched = self.fetch_cluster(project, location, cluster).await?;
+        let status = cluster_status(fetched.value.status);
+
+        Ok(Fetched::new(status, fetched.retries))
+    }
 
-        let response = client
-            .get_cluster(Request::new(GetClusterRequest {
-                name: format!(
-                    "projects/{}/locations/{}/clusters/{}",
-                    project, location, cluster
-                ),
+    async fn fetch_cluster(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+    ) -> Result<Fetched<Cluster>> {
+        let client = self.client().await?;
+
+        let name = format!(
+            "projects/{}/locations/{}/clusters/{}",
+            project, location, cluster
+        );
+        let response = retry(&self.retry_policy, || {
+            let mut client = client.clone();
+            let request = Request::new(GetClusterRequest {
+                name: name.clone(),
                 ..Default::default()
-            }))
-            .await
-            .map_err(|e| {
-                let msg = format!("{}", e.message());
-                anyhow::Error::new(e).context(msg)
-            })?;
-
-        let status = match response.into_inner().status {
-            0 => cluster::Status::Unspecified,
-            1 => cluster::Status::Provisioning,
-            2 => cluster::Status::Running,
-            3 => cluster::Status::Reconciling,
-            4 => cluster::Status::Stopping,
-            5 => cluster::Status::Error,
-            6 => cluster::Status::Degraded,
-            _ => panic!("none status"),
-        };
+            });
+            async move { client.get_cluster(request).await }
+        })
+        .await?;
 
-        Ok(status)
+        Ok(Fetched::new(response.value.into_inner(), response.retries))
     }
 
     async fn fetch_node_pool_status(
         &self,
-        project: &String,
-        location: &String,
-        cluster: &String,
-        node_pool: &String,
-    ) -> Result<node_pool::Status> {
-        let token = Token::new().map_err(|e| {
-            let msg = format!("{}", e);
-            anyhow::Error::new(e).context(msg)
-        })?;
-
-        let tls_config = ClientTlsConfig::new()
-            .ca_certificate(Certificate::from_pem(CERTIFICATES))
-            .domain_name("container.googleapis.com");
-
-        let channel = Channel::from_static("https://container.googleapis.com")
-            .tls_config(tls_config)?
-            .connect()
-            .await
-            .map_err(|e| {
-                let msg = format!("{}", e);
-                anyhow::Error::new(e).context(msg)
-            })?;
-
-        let mut client =
-            ClusterManagerClient::with_interceptor(channel, move |mut request: Request<()>| {
-                let token = &*token.header_value().unwrap();
-                let meta = MetadataValue::from_str(token).unwrap();
-                request.metadata_mut().insert("authorization", meta);
-                Ok(request)
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+    ) -> Result<Fetched<NodePoolStatus>> {
+        let fetched = self
+            .fetch_node_pool(project, location, cluster, node_pool)
+            .await?;
+        let status = node_pool_status(fetched.value.status);
+
+        Ok(Fetched::new(status, fetched.retries))
+    }
+
+    async fn fetch_node_pool(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+    ) -> Result<Fetched<NodePool>> {
+        let client = self.client().await?;
+
+        let name = format!(
+            "projects/{}/locations/{}/clusters/{}/nodePools/{}",
+            project, location, cluster, node_pool
+        );
+        let response = retry(&self.retry_policy, || {
+            let mut client = client.clone();
+            let request = Request::new(GetNodePoolRequest {
+                name: name.clone(),
+                ..Default::default()
             });
+            async move { client.get_node_pool(request).await }
+        })
+        .await?;
+
+        Ok(Fetched::new(response.value.into_inner(), response.retries))
+    }
+
+    async fn fetch_node_pool_detail(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+    ) -> Result<Fetched<NodePoolDetail>> {
+        let http_client = self.rest_client().await?;
+
+        let url = self.endpoint.rest_url(&format!(
+            "projects/{}/locations/{}/clusters/{}/nodePools/{}",
+            project, location, cluster, node_pool
+        ));
+        self.get(&http_client, &url).await
+    }
+
+    async fn fetch_instance_group_size(&self, instance_group_url: &str) -> Result<Fetched<u64>> {
+        let url = compute_url(
+            self.compute_endpoint
+                .as_deref()
+                .unwrap_or(DEFAULT_COMPUTE_ENDPOINT),
+            instance_group_url,
+        )?;
+        let fetched = self.get::<InstanceGroup>(&self.http_client, &url).await?;
+
+        Ok(Fetched::new(
+            fetched.value.size.max(0) as u64,
+            fetched.retries,
+        ))
+    }
+
+    async fn list_clusters(
+        &self,
+        project: &str,
+        location: &str,
+    ) -> Result<Fetched<Vec<ClusterSummary>>> {
+        let client = self.client().await?;
 
-        let response = client
-            .get_node_pool(Request::new(GetNodePoolRequest {
-                name: format!(
-                    "projects/{}/locations/{}/clusters/{}/nodePools/{}",
-                    project, location, cluster, node_pool
-                ),
+        let parent = format!("projects/{}/locations/{}", project, location);
+        let response = retry(&self.retry_policy, || {
+            let mut client = client.clone();
+            let request = Request::new(ListClustersRequest {
+                parent: parent.clone(),
                 ..Default::default()
-            }))
+            });
+            async move { client.list_clusters(request).await }
+        })
+        .await?;
+
+        let retries = response.retries;
+        let response = response.value.into_inner();
+        if !response.missing_zones.is_empty() {
+            log::warn!(
+                "clusters in some zones are not listed: {}",
+                response.missing_zones.join(", ")
+            );
+        }
+        let clusters = response
+            .clusters
+            .into_iter()
+            .map(|cluster| ClusterSummary {
+                name: cluster.name,
+                location: cluster.location,
+                status: cluster_status(cluster.status),
+            })
+            .collect();
+
+        Ok(Fetched::new(clusters, retries))
+    }
+
+    async fn list_node_pools(
+        &self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+    ) -> Result<Fetched<Vec<NodePoolSummary>>> {
+        let client = self.client().await?;
+
+        let parent = format!(
+            "projects/{}/locations/{}/clusters/{}",
+            project, location, cluster
+        );
+        let response = retry(&self.retry_policy, || {
+            let mut client = client.clone();
+            let request = Request::new(ListNodePoolsRequest {
+                parent: parent.clone(),
+                ..Default::default()
+            });
+            async move { client.list_node_pools(request).await }
+        })
+        .await?;
+
+        let node_pools = response
+            .value
+            .into_inner()
+            .node_pools
+            .into_iter()
+            .map(|node_pool| NodePoolSummary {
+                name: node_pool.name,
+                status: node_pool_status(node_pool.status),
+            })
+            .collect();
+
+        Ok(Fetched::new(node_pools, response.retries))
+    }
+}
+
+// InstanceGroup is the part of instance group resource of Compute Engine API used by yubi.
+#[derive(Deserialize)]
+struct InstanceGroup {
+    #[serde(default)]
+    size: i32,
+}
+
+// ErrorResponse is the error of Compute Engine API and GKE REST API.
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetail,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetail {
+    message: String,
+}
+
+// compute_url converts the url of instance group manager in node pool, which is returned with the
+// public host of Compute Engine API, to the url of instance group with the same name on endpoint.
+fn compute_url(compute_endpoint: &str, instance_group_url: &str) -> Result<String> {
+    let path = instance_group_url
+        .find("/projects/")
+        .map(|start| &instance_group_url[start..])
+        .filter(|path| path.contains("/instanceGroupManagers/"))
+        .ok_or_else(|| anyhow!("invalid instance group url: {}", instance_group_url))?;
+    Ok(format!(
+        "{}{}",
+        compute_endpoint.trim_end_matches('/'),
+        path.replace("/instanceGroupManagers/", "/instanceGroups/")
+    ))
+}
+
+// errors of REST API are converted to gRPC status, so that they are retried like errors of GKE
+// API.
+async fn get_json<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, Status> {
+    let response = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            Status::deadline_exceeded(format!("{}", e))
+        } else {
+            Status::unavailable(format!("{}", e))
+        }
+    })?;
+
+    let status = response.status();
+    if status.is_success() {
+        return response
+            .json::<T>()
             .await
-            .map_err(|e| {
-                let msg = format!("{}", e.message());
-                anyhow::Error::new(e).context(msg)
-            })?;
-
-        let status = match response.into_inner().status {
-            0 => node_pool::Status::Unspecified,
-            1 => node_pool::Status::Provisioning,
-            2 => node_pool::Status::Running,
-            3 => node_pool::Status::RunningWithError,
-            4 => node_pool::Status::Reconciling,
-            5 => node_pool::Status::Stopping,
-            6 => node_pool::Status::Error,
-            _ => panic!("none status"),
-        };
+            .map_err(|e| Status::internal(format!("{}", e)));
+    }
+    let body = response.text().await.unwrap_or_default();
+    let message = match serde_json::from_str::<ErrorResponse>(&body) {
+        Ok(response) => response.error.message,
+        Err(_) => format!("{}", status),
+    };
+    Err(Status::new(http_code(status), message))
+}
+
+fn http_code(status: reqwest::StatusCode) -> Code {
+    match status.as_u16() {
+        400 => Code::InvalidArgument,
+        401 => Code::Unauthenticated,
+        403 => Code::PermissionDenied,
+        404 => Code::NotFound,
+        429 => Code::ResourceExhausted,
+        503 => Code::Unavailable,
+        504 => Code::DeadlineExceeded,
+        _ => Code::Unknown,
+    }
+}
+
+fn cluster_status(status: i32) -> ClusterStatus {
+    match status {
+        0 => ClusterStatus::Unspecified,
+        1 => ClusterStatus::Provisioning,
+        2 => ClusterStatus::Running,
+        3 => ClusterStatus::Reconciling,
+        4 => ClusterStatus::Stopping,
+        5 => ClusterStatus::Error,
+        6 => ClusterStatus::Degraded,
+        number => ClusterStatus::Unknown(number),
+    }
+}
+
+fn node_pool_status(status: i32) -> NodePoolStatus {
+    match status {
+        0 => NodePoolStatus::Unspecified,
+        1 => NodePoolStatus::Provisioning,
+        2 => NodePoolStatus::Running,
+        3 => NodePoolStatus::RunningWithError,
+        4 => NodePoolStatus::Reconciling,
+        5 => NodePoolStatus::Stopping,
+        6 => NodePoolStatus::Error,
+        number => NodePoolStatus::Unknown(number),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        "https://compute.googleapis.com/compute/v1",
+        "https://www.googleapis.com/compute/v1/projects/project/zones/zone-a/instanceGroupManagers/gke-pool-grp",
+        Ok(format!("https://compute.googleapis.com/compute/v1/projects/project/zones/zone-a/instanceGroups/gke-pool-grp"))
+    )]
+    #[case(
+        "http://127.0.0.1:8080/compute/v1/",
+        "https://www.googleapis.com/compute/v1/projects/project/zones/zone-a/instanceGroupManagers/gke-pool-grp",
+        Ok(format!("http://127.0.0.1:8080/compute/v1/projects/project/zones/zone-a/instanceGroups/gke-pool-grp"))
+    )]
+    #[case(
+        "https://compute.googleapis.com/compute/v1",
+        "zone-a",
+        Err(format!("invalid instance group url: zone-a"))
+    )]
+    #[trace]
+    fn test_compute_url(
+        #[case] compute_endpoint: &str,
+        #[case] instance_group_url: &str,
+        #[case] expected: Result<String, String>,
+    ) {
+        assert_eq!(
+            compute_url(compute_endpoint, instance_group_url).map_err(|e| format!("{}", e)),
+            expected
+        );
+    }
 
-        Ok(status)
+    #[rstest]
+    #[case(404, Code::NotFound)]
+    #[case(403, Code::PermissionDenied)]
+    #[case(429, Code::ResourceExhausted)]
+    #[case(503, Code::Unavailable)]
+    #[case(500, Code::Unknown)]
+    #[trace]
+    fn test_http_code(#[case] status: u16, #[case] expected: Code) {
+        assert_eq!(
+            http_code(reqwest::StatusCode::from_u16(status).unwrap()),
+            expected
+        );
     }
 }
@@ -0,0 +1,168 @@
+use anyhow::Result;
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+use tonic::{Code, Status};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, initial_backoff: Duration, max_backoff: Duration) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff,
+            max_backoff,
+        }
+    }
+
+    // exponential backoff capped by max_backoff, jittered between half and full delay.
+    fn backoff(&self, retries: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retries))
+            .min(self.max_backoff);
+        exponential.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Fetched<T> {
+    pub value: T,
+    pub retries: u32,
+}
+
+impl<T> Fetched<T> {
+    pub fn new(value: T, retries: u32) -> Fetched<T> {
+        Fetched { value, retries }
+    }
+}
+
+pub fn is_retryable(code: Code) -> bool {
+    matches!(
+        code,
+        Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted
+    )
+}
+
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, mut call: F) -> Result<Fetched<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+{
+    let mut retries = 0;
+    loop {
+        match call().await {
+            Ok(value) => return Ok(Fetched::new(value, retries)),
+            Err(status) if is_retryable(status.code()) && retries < policy.max_retries => {
+                let backoff = policy.backoff(retries);
+                log::debug!(
+                    "retry after {:?}: {}: {}",
+                    backoff,
+                    status.code(),
+                    status.message()
+                );
+                tokio::time::sleep(backoff).await;
+                retries += 1;
+            }
+            Err(status) => {
+                let msg = if retries > 0 {
+                    format!("{} (retry count: {})", status.message(), retries)
+                } else {
+                    format!("{}", status.message())
+                };
+                return Err(anyhow::Error::new(status).context(msg));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::retry::*;
+    use rstest::*;
+    use std::cell::Cell;
+
+    #[fixture]
+    fn fixture_policy() -> RetryPolicy {
+        RetryPolicy::new(2, Duration::from_millis(1), Duration::from_millis(4))
+    }
+
+    #[rstest]
+    #[case(Code::Unavailable, true)]
+    #[case(Code::DeadlineExceeded, true)]
+    #[case(Code::ResourceExhausted, true)]
+    #[case(Code::NotFound, false)]
+    #[case(Code::PermissionDenied, false)]
+    #[case(Code::Unauthenticated, false)]
+    #[case(Code::InvalidArgument, false)]
+    #[trace]
+    fn test_is_retryable(#[case] code: Code, #[case] expected: bool) {
+        assert_eq!(is_retryable(code), expected);
+    }
+
+    #[rstest]
+    #[case(0, Duration::from_millis(500), Duration::from_millis(1000))]
+    #[case(1, Duration::from_millis(1000), Duration::from_millis(2000))]
+    #[case(2, Duration::from_millis(2000), Duration::from_millis(4000))]
+    #[case(10, Duration::from_millis(5000), Duration::from_millis(10000))]
+    #[trace]
+    fn test_backoff(#[case] retries: u32, #[case] min: Duration, #[case] max: Duration) {
+        let policy = RetryPolicy::new(3, Duration::from_secs(1), Duration::from_secs(10));
+        let backoff = policy.backoff(retries);
+        assert!(min <= backoff && backoff <= max, "{:?}", backoff);
+    }
+
+    #[rstest]
+    #[case(vec![], Ok(Fetched::new(format!("value"), 0)))]
+    #[case(vec![Code::Unavailable], Ok(Fetched::new(format!("value"), 1)))]
+    #[case(
+        vec![Code::Unavailable, Code::DeadlineExceeded],
+        Ok(Fetched::new(format!("value"), 2))
+    )]
+    #[case(
+        vec![Code::Unavailable, Code::Unavailable, Code::Unavailable],
+        Err(format!("error_message (retry count: 2)"))
+    )]
+    #[case(vec![Code::NotFound], Err(format!("error_message")))]
+    #[case(
+        vec![Code::Unavailable, Code::PermissionDenied],
+        Err(format!("error_message (retry count: 1)"))
+    )]
+    #[trace]
+    #[tokio::test]
+    async fn test_retry(
+        fixture_policy: RetryPolicy,
+        #[case] failures: Vec<Code>,
+        #[case] expected: Result<Fetched<String>, String>,
+    ) {
+        let calls = Cell::new(0);
+        let result = retry(&fixture_policy, || {
+            let call = calls.get();
+            calls.set(call + 1);
+            let response = match failures.get(call) {
+                Some(code) => Err(Status::new(*code, "error_message")),
+                None => Ok(format!("value")),
+            };
+            async move { response }
+        })
+        .await
+        .map_err(|e| format!("{}", e));
+
+        assert_eq!(result, expected);
+    }
+}
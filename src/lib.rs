@@ -1,4 +1,6 @@
 pub mod client;
 pub mod operator;
 pub mod report;
+pub mod runner;
 pub mod spec;
+pub mod specfile;
@@ -1,63 +1,317 @@
 extern crate clap;
 extern crate env_logger;
 
-use anyhow::{Context, Result};
-use clap::Parser;
-use log;
-use std::fs;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
 use std::io::{stdout, BufWriter, Write};
-use yubi::report::Report;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use yubi::client::endpoint::{Endpoint, DEFAULT_COMPUTE_ENDPOINT, DEFAULT_GKE_ENDPOINT};
+use yubi::client::gke_client::{GKEClient, GKEClientTrait};
+use yubi::client::retry::RetryPolicy;
+use yubi::report::exit_status::ExitStatus;
+use yubi::report::format::Format;
+use yubi::runner::{RunOptions, Runner};
+use yubi::spec::filter::SpecFilter;
 use yubi::spec::Spec;
-
-enum ExitStatus {
-    Success = 0,
-    Failure = 1,
-}
+use yubi::specfile;
+use yubi::specfile::generator;
+use yubi::specfile::schema::schema;
+use yubi::specfile::settings::Settings;
+use yubi::specfile::validation::validate;
 
 #[derive(Parser, Debug)]
-#[clap(about, author, version)]
+#[clap(
+    about,
+    author,
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
-    #[clap(name = "SPEC_FILE", help = "Path to specfile")]
-    specfile: String,
+    #[clap(subcommand)]
+    command: Option<Command>,
+    #[clap(
+        name = "SPEC_FILE",
+        required = true,
+        help = "Paths or glob patterns of specfiles"
+    )]
+    specfiles: Vec<String>,
+    #[clap(short, long, value_enum, help = "Format of report [default: yaml]")]
+    format: Option<Format>,
+    #[clap(
+        short,
+        long,
+        help = "Maximum number of specs checked at the same time [default: 4]"
+    )]
+    concurrency: Option<NonZeroUsize>,
+    #[clap(
+        short,
+        long,
+        value_parser = humantime::parse_duration,
+        help = "Timeout of each spec check (e.g. 30s, 5m), unless the spec sets its own timeout. With --wait, deadline of the whole wait"
+    )]
+    timeout: Option<Duration>,
+    #[clap(
+        short,
+        long,
+        help = "Re-check failing specs until all of them succeed or --timeout passes"
+    )]
+    wait: bool,
+    #[clap(
+        short,
+        long,
+        default_value = "15s",
+        value_parser = humantime::parse_duration,
+        requires = "wait",
+        help = "Interval between checks in --wait mode"
+    )]
+    interval: Duration,
+    #[clap(
+        long,
+        global = true,
+        default_value = "3",
+        help = "Maximum number of retries for transient GKE API errors"
+    )]
+    retries: u32,
+    #[clap(
+        long,
+        global = true,
+        default_value = "500ms",
+        value_parser = humantime::parse_duration,
+        help = "Initial backoff between retries, doubled on each retry"
+    )]
+    retry_backoff: Duration,
+    #[clap(
+        long,
+        global = true,
+        help = "URL of GKE API endpoint [default: https://container.googleapis.com]"
+    )]
+    endpoint: Option<String>,
+    #[clap(
+        long,
+        global = true,
+        help = "Connect to GKE API endpoint without TLS and authentication"
+    )]
+    plaintext: bool,
+    #[clap(
+        long,
+        global = true,
+        conflicts_with = "plaintext",
+        help = "Path to PEM encoded CA certificate of GKE API endpoint"
+    )]
+    ca_certificate: Option<String>,
+    #[clap(
+        long,
+        global = true,
+        help = "URL of Compute Engine API endpoint, used to count nodes of node pools [default: https://compute.googleapis.com/compute/v1]"
+    )]
+    compute_endpoint: Option<String>,
+    #[clap(
+        long = "id",
+        value_name = "ID",
+        help = "Check only specs with this id, can be repeated"
+    )]
+    ids: Vec<String>,
+    #[clap(
+        long = "tag",
+        value_name = "TAG",
+        help = "Check only specs with this tag, can be repeated"
+    )]
+    tags: Vec<String>,
+    #[clap(
+        long = "skip-tag",
+        value_name = "TAG",
+        help = "Skip specs with this tag, can be repeated"
+    )]
+    skip_tags: Vec<String>,
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     env_logger::init();
     log::debug!("start process");
 
+    let exit_status = match run().await {
+        Ok(exit_status) => exit_status,
+        Err(error) => {
+            eprintln!("Error: {:?}", error);
+            ExitStatus::Error
+        }
+    };
+
+    log::debug!("exit process");
+    std::process::exit(exit_status.code())
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[clap(about = "Validate specfiles without calling GKE API")]
+    Validate {
+        #[clap(
+            name = "SPEC_FILE",
+            required = true,
+            help = "Paths or glob patterns of specfiles"
+        )]
+        specfiles: Vec<String>,
+    },
+    #[clap(about = "Print JSON Schema of specfile")]
+    Schema,
+    #[clap(about = "Generate specfile from clusters and node pools of a project")]
+    Init {
+        #[clap(long, help = "Project of clusters")]
+        project: String,
+        #[clap(
+            long,
+            default_value = "-",
+            help = "Location of clusters, \"-\" means all locations"
+        )]
+        location: String,
+    },
+}
+
+async fn run() -> Result<ExitStatus> {
     log::debug!("parse command line args");
-    let args = Args::parse();
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        // --help and --version are not errors.
+        Err(error) if !error.use_stderr() => {
+            error.print()?;
+            return Ok(ExitStatus::Success);
+        }
+        Err(error) => {
+            error.print()?;
+            return Ok(ExitStatus::InvalidSpecfile);
+        }
+    };
 
-    log::debug!("parse specfile");
-    let specfile = fs::read_to_string(&args.specfile)
-        .with_context(|| format!("failed to open specfile: {}", &args.specfile))?;
-    let specs = serde_yaml::from_str::<Vec<Spec>>(&specfile)
-        .with_context(|| format!("failed to parse specfile: {}", &args.specfile))?;
+    match &args.command {
+        Some(Command::Validate { specfiles }) => return run_validate(specfiles),
+        Some(Command::Schema) => {
+            let out = stdout();
+            let mut out = BufWriter::new(out.lock());
+            writeln!(out, "{}", serde_json::to_string_pretty(&schema())?)?;
+            out.flush()?;
+            return Ok(ExitStatus::Success);
+        }
+        Some(Command::Init { project, location }) => {
+            log::debug!("generate specfile");
+            let client = gke_client(&args, &Settings::default());
+            let specs = generator::generate(&client, project, location).await?;
+            let out = stdout();
+            let mut out = BufWriter::new(out.lock());
+            write!(out, "{}", generator::render(&specs)?)?;
+            out.flush()?;
+            return Ok(ExitStatus::Success);
+        }
+        None => {}
+    }
 
-    log::debug!("check specs");
-    let mut report = Report::new();
-    for spec in specs {
-        match spec.check().await {
-            Ok(spec_result) => {
-                report.record_ok(spec, spec_result);
-            }
-            Err(error) => {
-                report.record_ng(spec, error);
-            }
+    log::debug!("parse specfile");
+    let specfile = match specfile::loader::load(&args.specfiles) {
+        Ok(specfile) => specfile,
+        Err(error) => {
+            eprintln!("Error: {:?}", error);
+            return Ok(ExitStatus::InvalidSpecfile);
         }
+    };
+
+    // command line options are preferred over settings of specfile.
+    let settings = specfile.settings;
+    let format = args.format.or(settings.format).unwrap_or(Format::Yaml);
+    let concurrency = args.concurrency.or(settings.concurrency);
+    let timeout = args.timeout.or(settings.timeout);
+    if args.wait && timeout.is_none() {
+        eprintln!("Error: --wait requires --timeout or timeout of settings");
+        return Ok(ExitStatus::InvalidSpecfile);
     }
+    let filter = SpecFilter::new(args.ids.clone(), args.tags.clone(), args.skip_tags.clone());
+    let specs = specfile
+        .specs
+        .into_iter()
+        .filter(|spec| filter.matches(spec))
+        .collect::<Vec<Spec>>();
+    // mistyped filter must not be reported as success of no specs.
+    if !filter.is_empty() && specs.is_empty() {
+        eprintln!("Error: no spec matches {}", filter);
+        return Ok(ExitStatus::InvalidSpecfile);
+    }
+
+    log::debug!("check specs");
+    let runner = Runner::new(
+        gke_client(&args, &settings),
+        RunOptions {
+            concurrency,
+            timeout,
+            wait: args.wait,
+            interval: args.interval,
+        },
+    );
+    let report = runner.run(specs).await;
 
     log::debug!("print report");
     let out = stdout();
     let mut out = BufWriter::new(out.lock());
-    writeln!(out, "{}", serde_yaml::to_string(&report)?)?;
+    writeln!(out, "{}", format.render(&report)?)?;
     out.flush()?;
 
-    log::debug!("exit process");
-    if report.is_all_green() {
-        std::process::exit(ExitStatus::Success as i32)
+    Ok(report.exit_status())
+}
+
+// endpoint options are preferred over settings of specfile, like other options.
+fn gke_client(args: &Args, settings: &Settings) -> Arc<dyn GKEClientTrait> {
+    let retry_policy = RetryPolicy {
+        max_retries: args.retries,
+        initial_backoff: args.retry_backoff,
+        ..Default::default()
+    };
+    let options = Settings {
+        endpoint: args.endpoint.clone(),
+        plaintext: args.plaintext.then_some(true),
+        ca_certificate: args.ca_certificate.clone(),
+        compute_endpoint: args.compute_endpoint.clone(),
+        ..Default::default()
+    }
+    .or(settings.clone());
+    let endpoint = Endpoint::new(
+        options
+            .endpoint
+            .unwrap_or_else(|| format!("{}", DEFAULT_GKE_ENDPOINT)),
+        options.plaintext.unwrap_or(false),
+        options.ca_certificate,
+    );
+    let compute_endpoint = options
+        .compute_endpoint
+        .unwrap_or_else(|| format!("{}", DEFAULT_COMPUTE_ENDPOINT));
+    Arc::new(GKEClient::new(endpoint, retry_policy).with_compute_endpoint(compute_endpoint))
+}
+
+fn run_validate(specfiles: &[String]) -> Result<ExitStatus> {
+    log::debug!("validate specfiles");
+    let validation = validate(specfiles);
+
+    let out = stdout();
+    let mut out = BufWriter::new(out.lock());
+    for problem in &validation.problems {
+        writeln!(out, "{}", problem)?;
+    }
+    if validation.is_valid() {
+        writeln!(
+            out,
+            "{} specs in {} specfiles are valid",
+            validation.specs, validation.specfiles
+        )?;
+        out.flush()?;
+        Ok(ExitStatus::Success)
     } else {
-        std::process::exit(ExitStatus::Failure as i32)
+        writeln!(
+            out,
+            "found {} problems in {} specfiles",
+            validation.problems.len(),
+            validation.specfiles
+        )?;
+        out.flush()?;
+        Ok(ExitStatus::InvalidSpecfile)
     }
 }
@@ -1,2 +1,94 @@
 pub mod gke_cluster_status_operator;
+pub mod gke_cluster_version_operator;
+pub mod gke_node_pool_autoscaling_operator;
+pub mod gke_node_pool_config_operator;
+pub mod gke_node_pool_size_operator;
 pub mod gke_node_pool_status_operator;
+pub mod gke_node_pool_version_operator;
+pub mod registry;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::client::gke_client::GKEClientTrait;
+use crate::spec::result::SpecResult;
+use crate::specfile::validation::Diagnosis;
+
+// Operator checks a resource. it is deserialized from keys of spec other than common keys
+// like id and tags, and serialized back into report.
+// operators are found by NAME in registry, see registry::register to add an operator.
+#[async_trait]
+pub trait Operator:
+    Serialize + DeserializeOwned + JsonSchema + fmt::Debug + Send + Sync + 'static
+{
+    // NAME is the value of operator key in spec, like "GKEClusterStatus".
+    const NAME: &'static str;
+
+    // target is the resource checked by the spec, like "project/location/cluster".
+    fn target(&self) -> String;
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult>;
+
+    // validate rejects a combination of keys which deserializer accepts but the operator can not
+    // check. it is called when spec is deserialized, and accepts any operator by default.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    // diagnose is used by validate to explain invalid nodes of spec which the deserializer can
+    // not point at, like unknown statuses, with their paths in spec. diagnosed nodes are
+    // replaced with valid values, so that the rest of spec is still checked.
+    // nothing is diagnosed by default.
+    fn diagnose(_spec: &mut serde_yaml::Value) -> Vec<Diagnosis> {
+        vec![]
+    }
+}
+
+// DynOperator is the object safe form of Operator, which is held by spec.
+// it is implemented for every Operator.
+#[async_trait]
+pub trait DynOperator: fmt::Debug + Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn target(&self) -> String;
+
+    // config is the operator serialized into a mapping, without operator key.
+    fn config(&self) -> serde_yaml::Result<serde_yaml::Value>;
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult>;
+}
+
+#[async_trait]
+impl<T> DynOperator for T
+where
+    T: Operator,
+{
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn target(&self) -> String {
+        Operator::target(self)
+    }
+
+    fn config(&self) -> serde_yaml::Result<serde_yaml::Value> {
+        serde_yaml::to_value(self)
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        Operator::check(self, client).await
+    }
+}
+
+impl PartialEq for dyn DynOperator {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name() && self.config().ok() == other.config().ok()
+    }
+}
+
+impl Eq for dyn DynOperator {}
@@ -1,16 +1,22 @@
 use anyhow::Result;
-use googapis::google::container::v1::cluster;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use crate::client::gke_client::GKEClientTrait;
-use crate::spec::cluster_status::ClusterStatus;
+use crate::operator::Operator;
+use crate::spec::cluster_status::{self, ClusterStatus};
 use crate::spec::result::SpecResult;
+use crate::specfile::validation::{unknown_statuses, Diagnosis};
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct GKEClusterStatusOperator {
-    project: String,
-    location: String,
-    cluster: String,
-    status: Vec<ClusterStatus>,
-    client: Box<dyn GKEClientTrait>,
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    pub status: Vec<ClusterStatus>,
 }
 
 impl GKEClusterStatusOperator {
@@ -19,53 +25,51 @@ impl GKEClusterStatusOperator {
         location: String,
         cluster: String,
         status: Vec<ClusterStatus>,
-        client: Box<dyn GKEClientTrait>,
     ) -> GKEClusterStatusOperator {
         GKEClusterStatusOperator {
-            project: project,
-            location: location,
-            cluster: cluster,
-            status: status,
-            client: client,
+            project,
+            location,
+            cluster,
+            status,
         }
     }
 
-    pub async fn check(&self) -> Result<SpecResult> {
-        let status = self
-            .client
-            .fetch_cluster_status(&self.project, &self.location, &self.cluster)
-            .await?;
-        self.compare(status)
+    fn compare(&self, cluster_status: ClusterStatus) -> SpecResult {
+        let description = format!("{} is {}", self.cluster, cluster_status);
+        if self.status.contains(&cluster_status) {
+            SpecResult::Success { description }
+        } else if let ClusterStatus::Unknown(_) = cluster_status {
+            SpecResult::Error { description }
+        } else {
+            SpecResult::Failure { description }
+        }
     }
+}
 
-    fn compare(&self, status: cluster::Status) -> Result<SpecResult> {
-        match status {
-            cluster::Status::Unspecified => Ok(self.compare_with(ClusterStatus::Unspecified)),
-            cluster::Status::Provisioning => Ok(self.compare_with(ClusterStatus::Provisioning)),
-            cluster::Status::Running => Ok(self.compare_with(ClusterStatus::Running)),
-            cluster::Status::Reconciling => Ok(self.compare_with(ClusterStatus::Reconciling)),
-            cluster::Status::Stopping => Ok(self.compare_with(ClusterStatus::Stopping)),
-            cluster::Status::Error => Ok(self.compare_with(ClusterStatus::Error)),
-            cluster::Status::Degraded => Ok(self.compare_with(ClusterStatus::Degraded)),
-        }
+#[async_trait]
+impl Operator for GKEClusterStatusOperator {
+    const NAME: &'static str = "GKEClusterStatus";
+
+    fn target(&self) -> String {
+        format!("{}/{}/{}", self.project, self.location, self.cluster)
     }
 
-    fn compare_with(&self, cluster_status: ClusterStatus) -> SpecResult {
-        if self.status.contains(&cluster_status) {
-            SpecResult::Success {
-                description: format!("{} is {}", self.cluster, cluster_status),
-            }
-        } else {
-            SpecResult::Failure {
-                description: format!("{} is {}", self.cluster, cluster_status),
-            }
-        }
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let fetched = client
+            .fetch_cluster_status(&self.project, &self.location, &self.cluster)
+            .await?;
+        Ok(self.compare(fetched.value).with_retries(fetched.retries))
+    }
+
+    fn diagnose(spec: &mut serde_yaml::Value) -> Vec<Diagnosis> {
+        unknown_statuses::<ClusterStatus>(spec, cluster_status::VARIANTS)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::client::gke_client::*;
+    use crate::client::retry::*;
     use crate::operator::gke_cluster_status_operator::*;
     use rstest::*;
 
@@ -73,73 +77,134 @@ mod tests {
     #[case(
         format!("cluster-001"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Unspecified,
+        ClusterStatus::Unspecified,
         SpecResult::Failure{description: format!("cluster-001 is Unspecified")}
     )]
     #[case(
         format!("cluster-002"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Provisioning,
+        ClusterStatus::Provisioning,
         SpecResult::Success{description: format!("cluster-002 is Provisioning")}
     )]
     #[case(
         format!("cluster-003"),
         vec![ClusterStatus::Provisioning,
         ClusterStatus::Running],
-        cluster::Status::Running,
+        ClusterStatus::Running,
         SpecResult::Success{description: format!("cluster-003 is Running")}
     )]
     #[case(
         format!("cluster-004"),
         vec![ClusterStatus::Provisioning,
         ClusterStatus::Running],
-        cluster::Status::Reconciling,
+        ClusterStatus::Reconciling,
         SpecResult::Failure{description: format!("cluster-004 is Reconciling")}
     )]
     #[case(
         format!("cluster-005"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Stopping,
+        ClusterStatus::Stopping,
         SpecResult::Failure{description: format!("cluster-005 is Stopping")}
     )]
     #[case(
         format!("cluster-006"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Error,
+        ClusterStatus::Error,
         SpecResult::Failure{description: format!("cluster-006 is Error")}
     )]
     #[case(
         format!("cluster-007"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Degraded,
+        ClusterStatus::Degraded,
         SpecResult::Failure{description: format!("cluster-007 is Degraded")}
     )]
+    #[case(
+        format!("cluster-101"),
+        vec![ClusterStatus::Provisioning, ClusterStatus::Running],
+        ClusterStatus::Unknown(99),
+        SpecResult::Error{description: format!("cluster-101 is Unknown(99)")}
+    )]
+    #[case(
+        format!("cluster-102"),
+        vec![ClusterStatus::Running, ClusterStatus::Unknown(99)],
+        ClusterStatus::Unknown(99),
+        SpecResult::Success{description: format!("cluster-102 is Unknown(99)")}
+    )]
     #[trace]
     async fn test_check(
         #[case] cluster: String,
         #[case] cluster_status: Vec<ClusterStatus>,
-        #[case] mocked_status: cluster::Status,
+        #[case] mocked_status: ClusterStatus,
+        #[case] expected: SpecResult,
+    ) {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster_status()
+            .returning(move |_, _, _| Ok(Fetched::new(mocked_status.clone(), 0)));
+
+        let operator = GKEClusterStatusOperator::new(
+            format!("project"),
+            format!("location"),
+            cluster,
+            cluster_status,
+        );
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        match operator.check(&client).await {
+            Ok(spec_result) => {
+                assert_eq!(spec_result, expected);
+            }
+            Err(_) => {
+                panic!("check gke cluster status error")
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(
+        format!("cluster-001"),
+        vec![ClusterStatus::Provisioning, ClusterStatus::Running],
+        Fetched::new(ClusterStatus::Running, 0),
+        SpecResult::Success{description: format!("cluster-001 is Running")}
+    )]
+    #[case(
+        format!("cluster-002"),
+        vec![ClusterStatus::Provisioning, ClusterStatus::Running],
+        Fetched::new(ClusterStatus::Running, 2),
+        SpecResult::Success{description: format!("cluster-002 is Running (retry count: 2)")}
+    )]
+    #[case(
+        format!("cluster-003"),
+        vec![ClusterStatus::Provisioning, ClusterStatus::Running],
+        Fetched::new(ClusterStatus::Error, 1),
+        SpecResult::Failure{description: format!("cluster-003 is Error (retry count: 1)")}
+    )]
+    #[trace]
+    async fn test_check_with_retries(
+        #[case] cluster: String,
+        #[case] cluster_status: Vec<ClusterStatus>,
+        #[case] mocked_fetched: Fetched<ClusterStatus>,
         #[case] expected: SpecResult,
     ) {
         let mut client = MockGKEClientTrait::new();
         client
             .expect_fetch_cluster_status()
-            .returning(move |_, _, _| Ok(mocked_status));
+            .returning(move |_, _, _| Ok(mocked_fetched.clone()));
 
         let operator = GKEClusterStatusOperator::new(
             format!("project"),
             format!("location"),
             cluster,
             cluster_status,
-            Box::new(client),
         );
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
 
-        match operator.check().await {
+        match operator.check(&client).await {
             Ok(spec_result) => {
                 assert_eq!(spec_result, expected);
             }
             Err(_) => {
-                assert!(false, "check gke cluster status error")
+                panic!("check gke cluster status error")
             }
         }
     }
@@ -149,50 +214,62 @@ mod tests {
         format!("cluster-001"),
         vec![ClusterStatus::Provisioning,
         ClusterStatus::Running],
-        cluster::Status::Unspecified,
+        ClusterStatus::Unspecified,
         SpecResult::Failure{description: format!("cluster-001 is Unspecified")}
     )]
     #[case(
         format!("cluster-002"),
         vec![ClusterStatus::Provisioning,ClusterStatus::Running],
-        cluster::Status::Provisioning,
+        ClusterStatus::Provisioning,
         SpecResult::Success{description: format!("cluster-002 is Provisioning")}
     )]
     #[case(
         format!("cluster-003"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Running,
+        ClusterStatus::Running,
         SpecResult::Success{description: format!("cluster-003 is Running")}
     )]
     #[case(
         format!("cluster-004"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Reconciling,
+        ClusterStatus::Reconciling,
         SpecResult::Failure{description: format!("cluster-004 is Reconciling")}
     )]
     #[case(
         format!("cluster-005"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Stopping,
+        ClusterStatus::Stopping,
         SpecResult::Failure{description: format!("cluster-005 is Stopping")}
     )]
     #[case(
         format!("cluster-006"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Error,
+        ClusterStatus::Error,
         SpecResult::Failure{description: format!("cluster-006 is Error")}
     )]
     #[case(
         format!("cluster-007"),
         vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        cluster::Status::Degraded,
+        ClusterStatus::Degraded,
         SpecResult::Failure{description: format!("cluster-007 is Degraded")}
     )]
+    #[case(
+        format!("cluster-101"),
+        vec![ClusterStatus::Provisioning, ClusterStatus::Running],
+        ClusterStatus::Unknown(99),
+        SpecResult::Error{description: format!("cluster-101 is Unknown(99)")}
+    )]
+    #[case(
+        format!("cluster-102"),
+        vec![ClusterStatus::Running, ClusterStatus::Unknown(99)],
+        ClusterStatus::Unknown(99),
+        SpecResult::Success{description: format!("cluster-102 is Unknown(99)")}
+    )]
     #[trace]
     fn test_compare(
         #[case] cluster: String,
         #[case] cluster_status: Vec<ClusterStatus>,
-        #[case] input_status: cluster::Status,
+        #[case] input_status: ClusterStatus,
         #[case] expected: SpecResult,
     ) {
         let operator = GKEClusterStatusOperator::new(
@@ -200,16 +277,8 @@ mod tests {
             format!("location"),
             cluster,
             cluster_status,
-            Box::new(GKEClient::new()),
         );
 
-        match operator.compare(input_status) {
-            Ok(spec_result) => {
-                assert_eq!(spec_result, expected)
-            }
-            Err(_) => {
-                assert!(false, "compare gke cluster status error")
-            }
-        }
+        assert_eq!(operator.compare(input_status), expected);
     }
 }
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::client::gke_client::GKEClientTrait;
+use crate::operator::Operator;
+use crate::spec::result::SpecResult;
+use crate::spec::version::{Version, VersionConstraint};
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GKEClusterVersionOperator {
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    pub version: VersionConstraint,
+}
+
+impl GKEClusterVersionOperator {
+    pub fn new(
+        project: String,
+        location: String,
+        cluster: String,
+        version: VersionConstraint,
+    ) -> GKEClusterVersionOperator {
+        GKEClusterVersionOperator {
+            project,
+            location,
+            cluster,
+            version,
+        }
+    }
+
+    fn compare(&self, master_version: &str) -> SpecResult {
+        let version = match master_version.parse::<Version>() {
+            Ok(version) => version,
+            Err(error) => {
+                return SpecResult::Error {
+                    description: format!("{} has {}", self.cluster, error),
+                }
+            }
+        };
+        if self.version.matches(&version) {
+            SpecResult::Success {
+                description: format!("{} is {}", self.cluster, version),
+            }
+        } else {
+            SpecResult::Failure {
+                description: format!("{} is {}, expected {}", self.cluster, version, self.version),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Operator for GKEClusterVersionOperator {
+    const NAME: &'static str = "GKEClusterVersion";
+
+    fn target(&self) -> String {
+        format!("{}/{}/{}", self.project, self.location, self.cluster)
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let fetched = client
+            .fetch_cluster(&self.project, &self.location, &self.cluster)
+            .await?;
+        Ok(self
+            .compare(&fetched.value.current_master_version)
+            .with_retries(fetched.retries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_cluster_version_operator::*;
+    use googapis::google::container::v1::Cluster;
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        ">=1.27, <1.30",
+        "1.27.3-gke.100",
+        SpecResult::Success{description: format!("cluster is 1.27.3-gke.100")}
+    )]
+    #[case(
+        ">=1.27, <1.30",
+        "1.30.1-gke.200",
+        SpecResult::Failure{description: format!("cluster is 1.30.1-gke.200, expected >=1.27, <1.30")}
+    )]
+    #[case(
+        "1.28",
+        "1.27.3-gke.100",
+        SpecResult::Failure{description: format!("cluster is 1.27.3-gke.100, expected =1.28")}
+    )]
+    #[case(
+        ">=1.27",
+        "",
+        SpecResult::Error{description: format!("cluster has invalid version: ")}
+    )]
+    #[trace]
+    fn test_compare(
+        #[case] constraint: &str,
+        #[case] master_version: &str,
+        #[case] expected: SpecResult,
+    ) {
+        let operator = GKEClusterVersionOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            constraint.parse().unwrap(),
+        );
+
+        assert_eq!(operator.compare(master_version), expected);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_check() {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster()
+            .withf(|project, location, cluster| {
+                (project, location, cluster) == ("project", "location", "cluster")
+            })
+            .returning(|_, _, _| {
+                Ok(Fetched::new(
+                    Cluster {
+                        current_master_version: format!("1.29.1-gke.300"),
+                        ..Default::default()
+                    },
+                    1,
+                ))
+            });
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let operator = GKEClusterVersionOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            VersionConstraint::Any(vec!["1.28".parse().unwrap(), "1.29".parse().unwrap()]),
+        );
+
+        assert_eq!(
+            operator.check(&client).await.unwrap(),
+            SpecResult::Success {
+                description: format!("cluster is 1.29.1-gke.300 (retry count: 1)")
+            }
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use googapis::google::container::v1::NodePoolAutoscaling;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::client::gke_client::{AutoscalingDetail, GKEClientTrait};
+use crate::operator::Operator;
+use crate::spec::range::Range;
+use crate::spec::result::SpecResult;
+use crate::spec::scalar::from_str_or;
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GKENodePoolAutoscalingOperator {
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    pub node_pool: String,
+    // whether autoscaling is expected to be enabled.
+    #[serde(default = "enabled", deserialize_with = "from_str_or")]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_node_count: Option<Range>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_node_count: Option<Range>,
+    // location policy like BALANCED or ANY, which is fetched from GKE REST API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location_policy: Option<String>,
+}
+
+impl GKENodePoolAutoscalingOperator {
+    pub fn new(
+        project: String,
+        location: String,
+        cluster: String,
+        node_pool: String,
+        enabled: bool,
+        min_node_count: Option<Range>,
+        max_node_count: Option<Range>,
+    ) -> GKENodePoolAutoscalingOperator {
+        GKENodePoolAutoscalingOperator {
+            project,
+            location,
+            cluster,
+            node_pool,
+            enabled,
+            min_node_count,
+            max_node_count,
+            location_policy: None,
+        }
+    }
+
+    // compare checks autoscaling of node pool. node counts are checked only when autoscaling
+    // is expected to be enabled, and every mismatch is listed in failure description.
+    fn compare(
+        &self,
+        autoscaling: Option<&NodePoolAutoscaling>,
+        detail: &AutoscalingDetail,
+    ) -> SpecResult {
+        let autoscaling = autoscaling.filter(|autoscaling| autoscaling.enabled);
+        let actual_location_policy = detail.location_policy.as_deref().unwrap_or("unset");
+        let description = match autoscaling {
+            Some(autoscaling) if self.location_policy.is_some() => format!(
+                "{} autoscaling is enabled with {}-{} nodes, location_policy {}",
+                self.node_pool,
+                autoscaling.min_node_count,
+                autoscaling.max_node_count,
+                actual_location_policy
+            ),
+            Some(autoscaling) => format!(
+                "{} autoscaling is enabled with {}-{} nodes",
+                self.node_pool, autoscaling.min_node_count, autoscaling.max_node_count
+            ),
+            None => format!("{} autoscaling is disabled", self.node_pool),
+        };
+
+        let mut mismatches = vec![];
+        match (self.enabled, autoscaling) {
+            (true, Some(autoscaling)) => {
+                let counts = [
+                    (
+                        "min_node_count",
+                        &self.min_node_count,
+                        autoscaling.min_node_count,
+                    ),
+                    (
+                        "max_node_count",
+                        &self.max_node_count,
+                        autoscaling.max_node_count,
+                    ),
+                ];
+                for (key, expected, actual) in counts {
+                    if let Some(expected) = expected {
+                        if !expected.contains(actual.max(0) as u64) {
+                            mismatches.push(format!("expected {} {}", key, expected));
+                        }
+                    }
+                }
+                // location policy is returned in upper case like "BALANCED", so case is ignored.
+                if let Some(expected) = &self.location_policy {
+                    if !actual_location_policy.eq_ignore_ascii_case(expected) {
+                        mismatches.push(format!("expected location_policy {}", expected));
+                    }
+                }
+            }
+            (true, None) => mismatches.push(format!("expected enabled")),
+            (false, Some(_)) => mismatches.push(format!("expected disabled")),
+            (false, None) => {}
+        }
+
+        if mismatches.is_empty() {
+            SpecResult::Success { description }
+        } else {
+            SpecResult::Failure {
+                description: format!("{}, {}", description, mismatches.join(", ")),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Operator for GKENodePoolAutoscalingOperator {
+    const NAME: &'static str = "GKENodePoolAutoscaling";
+
+    fn target(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.project, self.location, self.cluster, self.node_pool
+        )
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.enabled
+            && (self.min_node_count.is_some()
+                || self.max_node_count.is_some()
+                || self.location_policy.is_some())
+        {
+            return Err(format!(
+                "min_node_count, max_node_count and location_policy can not be checked with enabled: false"
+            ));
+        }
+        Ok(())
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let fetched = client
+            .fetch_node_pool(
+                &self.project,
+                &self.location,
+                &self.cluster,
+                &self.node_pool,
+            )
+            .await?;
+        let mut retries = fetched.retries;
+        // location policy costs another request to GKE REST API, so it is made only when expected.
+        let detail = match self.location_policy {
+            Some(_) => {
+                let detail = client
+                    .fetch_node_pool_detail(
+                        &self.project,
+                        &self.location,
+                        &self.cluster,
+                        &self.node_pool,
+                    )
+                    .await?;
+                retries += detail.retries;
+                detail.value.autoscaling
+            }
+            None => AutoscalingDetail::default(),
+        };
+        Ok(self
+            .compare(fetched.value.autoscaling.as_ref(), &detail)
+            .with_retries(retries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_node_pool_autoscaling_operator::*;
+    use crate::operator::registry::Registry;
+    use googapis::google::container::v1::NodePool;
+    use rstest::*;
+
+    fn autoscaling(enabled: bool, min_node_count: i32, max_node_count: i32) -> NodePoolAutoscaling {
+        NodePoolAutoscaling {
+            enabled,
+            min_node_count,
+            max_node_count,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case(
+        true,
+        Some(Range { min: Some(1), max: None }),
+        Some(Range { min: Some(5), max: Some(10) }),
+        Some(autoscaling(true, 1, 5)),
+        SpecResult::Success{description: format!("node_pool autoscaling is enabled with 1-5 nodes")}
+    )]
+    #[case(
+        true,
+        Some(Range { min: Some(1), max: None }),
+        Some(Range { min: Some(5), max: Some(10) }),
+        Some(autoscaling(true, 0, 3)),
+        SpecResult::Failure{description: format!("node_pool autoscaling is enabled with 0-3 nodes, expected min_node_count at least 1, expected max_node_count between 5 and 10")}
+    )]
+    #[case(
+        true,
+        None,
+        Some(Range { min: Some(5), max: None }),
+        Some(autoscaling(false, 1, 5)),
+        SpecResult::Failure{description: format!("node_pool autoscaling is disabled, expected enabled")}
+    )]
+    #[case(
+        true,
+        None,
+        None,
+        None,
+        SpecResult::Failure{description: format!("node_pool autoscaling is disabled, expected enabled")}
+    )]
+    #[case(
+        false,
+        None,
+        None,
+        None,
+        SpecResult::Success{description: format!("node_pool autoscaling is disabled")}
+    )]
+    #[case(
+        false,
+        None,
+        None,
+        Some(autoscaling(true, 1, 5)),
+        SpecResult::Failure{description: format!("node_pool autoscaling is enabled with 1-5 nodes, expected disabled")}
+    )]
+    #[trace]
+    fn test_compare(
+        #[case] enabled: bool,
+        #[case] min_node_count: Option<Range>,
+        #[case] max_node_count: Option<Range>,
+        #[case] actual: Option<NodePoolAutoscaling>,
+        #[case] expected: SpecResult,
+    ) {
+        let operator = GKENodePoolAutoscalingOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            format!("node_pool"),
+            enabled,
+            min_node_count,
+            max_node_count,
+        );
+
+        assert_eq!(
+            operator.compare(actual.as_ref(), &AutoscalingDetail::default()),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "balanced",
+        Some(format!("BALANCED")),
+        SpecResult::Success{description: format!("node_pool autoscaling is enabled with 1-5 nodes, location_policy BALANCED")}
+    )]
+    #[case(
+        "ANY",
+        Some(format!("BALANCED")),
+        SpecResult::Failure{description: format!("node_pool autoscaling is enabled with 1-5 nodes, location_policy BALANCED, expected location_policy ANY")}
+    )]
+    #[case(
+        "ANY",
+        None,
+        SpecResult::Failure{description: format!("node_pool autoscaling is enabled with 1-5 nodes, location_policy unset, expected location_policy ANY")}
+    )]
+    #[trace]
+    fn test_compare_location_policy(
+        #[case] location_policy: &str,
+        #[case] actual: Option<String>,
+        #[case] expected: SpecResult,
+    ) {
+        let mut operator = GKENodePoolAutoscalingOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            format!("node_pool"),
+            true,
+            None,
+            None,
+        );
+        operator.location_policy = Some(format!("{}", location_policy));
+
+        assert_eq!(
+            operator.compare(
+                Some(&autoscaling(true, 1, 5)),
+                &AutoscalingDetail {
+                    location_policy: actual
+                }
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("max_node_count: 5\n", Ok(()))]
+    #[case("enabled: false\n", Ok(()))]
+    #[case("location_policy: BALANCED\n", Ok(()))]
+    #[case(
+        "enabled: false\nmax_node_count: 5\n",
+        Err(format!("min_node_count, max_node_count and location_policy can not be checked with enabled: false"))
+    )]
+    #[case(
+        "enabled: false\nlocation_policy: BALANCED\n",
+        Err(format!("min_node_count, max_node_count and location_policy can not be checked with enabled: false"))
+    )]
+    #[case(
+        "max_nodes: 3\n",
+        Err(format!("unknown field `max_nodes`, expected one of `project`, `location`, `cluster`, `node_pool`, `enabled`, `min_node_count`, `max_node_count`, `location_policy`"))
+    )]
+    #[trace]
+    fn test_validate(#[case] config: &str, #[case] expected: Result<(), String>) {
+        let config = format!(
+            "project: project\nlocation: location\ncluster: cluster\nnode_pool: node_pool\n{}",
+            config
+        );
+        let actual = Registry::default()
+            .build(
+                GKENodePoolAutoscalingOperator::NAME,
+                serde_yaml::from_str(&config).unwrap(),
+            )
+            .unwrap()
+            .map(|_| ())
+            .map_err(|error| format!("{}", error));
+        assert_eq!(actual, expected);
+    }
+
+    // node pool detail is fetched only when location policy is expected.
+    #[rstest]
+    #[case(
+        "",
+        0,
+        SpecResult::Success{description: format!("node_pool autoscaling is enabled with 1-5 nodes")}
+    )]
+    #[case(
+        "location_policy: BALANCED\n",
+        1,
+        SpecResult::Success{description: format!("node_pool autoscaling is enabled with 1-5 nodes, location_policy BALANCED (retry count: 1)")}
+    )]
+    #[trace]
+    #[tokio::test]
+    async fn test_check(
+        #[case] expectations: &str,
+        #[case] detail_fetches: usize,
+        #[case] expected: SpecResult,
+    ) {
+        let mut client = MockGKEClientTrait::new();
+        client.expect_fetch_node_pool().returning(|_, _, _, _| {
+            Ok(Fetched::new(
+                NodePool {
+                    autoscaling: Some(autoscaling(true, 1, 5)),
+                    ..Default::default()
+                },
+                0,
+            ))
+        });
+        client
+            .expect_fetch_node_pool_detail()
+            .times(detail_fetches)
+            .returning(|_, _, _, _| {
+                Ok(Fetched::new(
+                    NodePoolDetail {
+                        autoscaling: AutoscalingDetail {
+                            location_policy: Some(format!("BALANCED")),
+                        },
+                        ..Default::default()
+                    },
+                    1,
+                ))
+            });
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let operator = serde_yaml::from_str::<GKENodePoolAutoscalingOperator>(&format!(
+            "project: project\nlocation: location\ncluster: cluster\nnode_pool: node_pool\nmax_node_count: 5\n{}",
+            expectations
+        ))
+        .unwrap();
+
+        assert!(operator.enabled);
+        assert_eq!(operator.check(&client).await.unwrap(), expected);
+    }
+}
@@ -0,0 +1,486 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use googapis::google::container::v1::{NodeConfig, NodeTaint};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::client::gke_client::{GKEClientTrait, NodeConfigDetail};
+use crate::operator::Operator;
+use crate::spec::range::Range;
+use crate::spec::result::SpecResult;
+use crate::spec::scalar::option_from_str_or;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone, Copy)]
+pub enum TaintEffect {
+    NoSchedule,
+    PreferNoSchedule,
+    NoExecute,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Taint {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    pub effect: TaintEffect,
+}
+
+// taints are shown like kubectl, as "key=value:Effect" or "key:Effect".
+impl fmt::Display for Taint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        format_taint(f, &self.key, &self.value, &format!("{:?}", self.effect))
+    }
+}
+
+fn format_taint(f: &mut fmt::Formatter, key: &str, value: &str, effect: &str) -> fmt::Result {
+    if value.is_empty() {
+        write!(f, "{}:{}", key, effect)
+    } else {
+        write!(f, "{}={}:{}", key, value, effect)
+    }
+}
+
+// ActualTaint shows taint returned by GKE API. effects which can not be expected, unspecified or
+// unknown to yubi, are shown as is, so such taint is always a mismatch.
+struct ActualTaint<'a>(&'a NodeTaint);
+
+impl fmt::Display for ActualTaint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let effect = match self.0.effect {
+            0 => format!("Unspecified"),
+            1 => format!("NoSchedule"),
+            2 => format!("PreferNoSchedule"),
+            3 => format!("NoExecute"),
+            number => format!("Unknown({})", number),
+        };
+        format_taint(f, &self.0.key, &self.0.value, &effect)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GKENodePoolConfigOperator {
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    pub node_pool: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_size_gb: Option<Range>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_type: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "option_from_str_or",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub preemptible: Option<bool>,
+    // labels which node pool must have. other labels are ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub labels: Option<BTreeMap<String, String>>,
+    // all taints of node pool, in any order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taints: Option<Vec<Taint>>,
+    // spot is missing in NodeConfig of googapis, so it is fetched from GKE REST API.
+    #[serde(
+        default,
+        deserialize_with = "option_from_str_or",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub spot: Option<bool>,
+}
+
+// Check is a checked field of node config, which is shown as "key actual" in description.
+struct Check {
+    key: String,
+    actual: String,
+    expected: String,
+    matched: bool,
+}
+
+impl GKENodePoolConfigOperator {
+    pub fn new(
+        project: String,
+        location: String,
+        cluster: String,
+        node_pool: String,
+    ) -> GKENodePoolConfigOperator {
+        GKENodePoolConfigOperator {
+            project,
+            location,
+            cluster,
+            node_pool,
+            machine_type: None,
+            disk_size_gb: None,
+            disk_type: None,
+            image_type: None,
+            preemptible: None,
+            labels: None,
+            taints: None,
+            spot: None,
+        }
+    }
+
+    fn checks(&self, config: &NodeConfig, detail: &NodeConfigDetail) -> Vec<Check> {
+        let mut checks = vec![];
+        if let Some(machine_type) = &self.machine_type {
+            checks.push(Check {
+                key: format!("machine_type"),
+                actual: config.machine_type.clone(),
+                expected: machine_type.clone(),
+                matched: &config.machine_type == machine_type,
+            });
+        }
+        if let Some(disk_size_gb) = &self.disk_size_gb {
+            checks.push(Check {
+                key: format!("disk_size_gb"),
+                actual: format!("{}", config.disk_size_gb),
+                expected: format!("{}", disk_size_gb),
+                matched: disk_size_gb.contains(config.disk_size_gb.max(0) as u64),
+            });
+        }
+        if let Some(disk_type) = &self.disk_type {
+            checks.push(Check {
+                key: format!("disk_type"),
+                actual: config.disk_type.clone(),
+                expected: disk_type.clone(),
+                matched: &config.disk_type == disk_type,
+            });
+        }
+        // image type is returned in upper case like "COS_CONTAINERD", so case is ignored.
+        if let Some(image_type) = &self.image_type {
+            checks.push(Check {
+                key: format!("image_type"),
+                actual: config.image_type.clone(),
+                expected: image_type.clone(),
+                matched: config.image_type.eq_ignore_ascii_case(image_type),
+            });
+        }
+        if let Some(preemptible) = self.preemptible {
+            checks.push(Check {
+                key: format!("preemptible"),
+                actual: format!("{}", config.preemptible),
+                expected: format!("{}", preemptible),
+                matched: config.preemptible == preemptible,
+            });
+        }
+        if let Some(spot) = self.spot {
+            checks.push(Check {
+                key: format!("spot"),
+                actual: format!("{}", detail.spot),
+                expected: format!("{}", spot),
+                matched: detail.spot == spot,
+            });
+        }
+        for (key, value) in self.labels.iter().flatten() {
+            let actual = config.labels.get(key);
+            checks.push(Check {
+                key: format!("labels.{}", key),
+                actual: actual.cloned().unwrap_or_else(|| format!("unset")),
+                expected: value.clone(),
+                matched: actual == Some(value),
+            });
+        }
+        if let Some(taints) = &self.taints {
+            let mut actual = config
+                .taints
+                .iter()
+                .map(|taint| format!("{}", ActualTaint(taint)))
+                .collect::<Vec<String>>();
+            let mut expected = taints
+                .iter()
+                .map(|taint| format!("{}", taint))
+                .collect::<Vec<String>>();
+            actual.sort();
+            expected.sort();
+            checks.push(Check {
+                key: format!("taints"),
+                actual: format!("[{}]", actual.join(", ")),
+                expected: format!("[{}]", expected.join(", ")),
+                matched: actual == expected,
+            });
+        }
+        checks
+    }
+
+    // compare checks only fields which have expectation, and every mismatch is listed in
+    // failure description.
+    fn compare(&self, config: &NodeConfig, detail: &NodeConfigDetail) -> SpecResult {
+        let checks = self.checks(config, detail);
+        if checks.is_empty() {
+            return SpecResult::Success {
+                description: format!("{} exists", self.node_pool),
+            };
+        }
+
+        let actuals = checks
+            .iter()
+            .map(|check| format!("{} {}", check.key, check.actual))
+            .collect::<Vec<String>>();
+        let description = format!("{} has {}", self.node_pool, actuals.join(", "));
+        let mismatches = checks
+            .iter()
+            .filter(|check| !check.matched)
+            .map(|check| format!("expected {} {}", check.key, check.expected))
+            .collect::<Vec<String>>();
+
+        if mismatches.is_empty() {
+            SpecResult::Success { description }
+        } else {
+            SpecResult::Failure {
+                description: format!("{}, {}", description, mismatches.join(", ")),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Operator for GKENodePoolConfigOperator {
+    const NAME: &'static str = "GKENodePoolConfig";
+
+    fn target(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.project, self.location, self.cluster, self.node_pool
+        )
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let fetched = client
+            .fetch_node_pool(
+                &self.project,
+                &self.location,
+                &self.cluster,
+                &self.node_pool,
+            )
+            .await?;
+        let mut retries = fetched.retries;
+        // node pool is fetched again only when fields missing in googapis are expected.
+        let detail = match self.spot {
+            Some(_) => {
+                let detail = client
+                    .fetch_node_pool_detail(
+                        &self.project,
+                        &self.location,
+                        &self.cluster,
+                        &self.node_pool,
+                    )
+                    .await?;
+                retries += detail.retries;
+                detail.value.config
+            }
+            None => NodeConfigDetail::default(),
+        };
+        let config = fetched.value.config.unwrap_or_default();
+        Ok(self.compare(&config, &detail).with_retries(retries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_node_pool_config_operator::*;
+    use crate::operator::registry::Registry;
+    use googapis::google::container::v1::NodePool;
+    use rstest::*;
+
+    fn config() -> NodeConfig {
+        NodeConfig {
+            machine_type: format!("e2-standard-4"),
+            disk_size_gb: 100,
+            disk_type: format!("pd-balanced"),
+            image_type: format!("COS_CONTAINERD"),
+            preemptible: false,
+            labels: [(format!("env"), format!("prod"))].into_iter().collect(),
+            taints: vec![NodeTaint {
+                key: format!("dedicated"),
+                value: format!("batch"),
+                effect: 1,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "",
+        SpecResult::Success{description: format!("node_pool exists")}
+    )]
+    #[case(
+        ", machine_type: e2-standard-4, disk_size_gb: {min: 100}, image_type: cos_containerd",
+        SpecResult::Success{description: format!("node_pool has machine_type e2-standard-4, disk_size_gb 100, image_type COS_CONTAINERD")}
+    )]
+    #[case(
+        ", machine_type: e2-medium, disk_size_gb: {min: 200}, disk_type: pd-balanced, preemptible: true",
+        SpecResult::Failure{description: format!("node_pool has machine_type e2-standard-4, disk_size_gb 100, disk_type pd-balanced, preemptible false, expected machine_type e2-medium, expected disk_size_gb at least 200, expected preemptible true")}
+    )]
+    #[case(
+        ", labels: {env: prod}, taints: [{key: dedicated, value: batch, effect: NoSchedule}]",
+        SpecResult::Success{description: format!("node_pool has labels.env prod, taints [dedicated=batch:NoSchedule]")}
+    )]
+    #[case(
+        ", labels: {env: dev, team: a}, taints: []",
+        SpecResult::Failure{description: format!("node_pool has labels.env prod, labels.team unset, taints [dedicated=batch:NoSchedule], expected labels.env dev, expected labels.team a, expected taints []")}
+    )]
+    #[case(
+        ", preemptible: false, spot: false",
+        SpecResult::Failure{description: format!("node_pool has preemptible false, spot true, expected spot false")}
+    )]
+    #[trace]
+    fn test_compare(#[case] expectations: &str, #[case] expected: SpecResult) {
+        let operator = serde_yaml::from_str::<GKENodePoolConfigOperator>(&format!(
+            "{{project: project, location: location, cluster: cluster, node_pool: node_pool{}}}",
+            expectations
+        ))
+        .unwrap();
+
+        assert_eq!(
+            operator.compare(&config(), &NodeConfigDetail { spot: true }),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(
+        Taint { key: format!("dedicated"), value: format!("batch"), effect: TaintEffect::NoSchedule },
+        "dedicated=batch:NoSchedule"
+    )]
+    #[case(
+        Taint { key: format!("gpu"), value: format!(""), effect: TaintEffect::NoExecute },
+        "gpu:NoExecute"
+    )]
+    #[trace]
+    fn test_taint_display(#[case] taint: Taint, #[case] expected: &str) {
+        assert_eq!(format!("{}", taint), expected);
+    }
+
+    #[rstest]
+    #[case(1, "dedicated=batch:NoSchedule")]
+    #[case(3, "dedicated=batch:NoExecute")]
+    #[case(0, "dedicated=batch:Unspecified")]
+    #[case(7, "dedicated=batch:Unknown(7)")]
+    #[trace]
+    fn test_actual_taint_display(#[case] effect: i32, #[case] expected: &str) {
+        let taint = NodeTaint {
+            key: format!("dedicated"),
+            value: format!("batch"),
+            effect,
+        };
+        assert_eq!(format!("{}", ActualTaint(&taint)), expected);
+    }
+
+    // taint with effect unknown to yubi can not be expected, so it never matches.
+    #[rstest]
+    #[case(0, "node_pool has taints [dedicated=batch:Unspecified], expected taints [dedicated=batch:NoSchedule]")]
+    #[case(7, "node_pool has taints [dedicated=batch:Unknown(7)], expected taints [dedicated=batch:NoSchedule]")]
+    #[trace]
+    fn test_compare_unknown_taint_effect(#[case] effect: i32, #[case] expected: &str) {
+        let mut operator = GKENodePoolConfigOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            format!("node_pool"),
+        );
+        operator.taints = Some(vec![Taint {
+            key: format!("dedicated"),
+            value: format!("batch"),
+            effect: TaintEffect::NoSchedule,
+        }]);
+        let mut config = config();
+        config.taints[0].effect = effect;
+
+        assert_eq!(
+            operator.compare(&config, &NodeConfigDetail::default()),
+            SpecResult::Failure {
+                description: format!("{}", expected)
+            }
+        );
+    }
+
+    #[rstest]
+    #[case("machine_type: e2-standard-4\n", Ok(()))]
+    #[case("spot: true\n", Ok(()))]
+    #[case(
+        "machine_typ: e2-standard-4\n",
+        Err(format!("unknown field `machine_typ`, expected one of `project`, `location`, `cluster`, `node_pool`, `machine_type`, `disk_size_gb`, `disk_type`, `image_type`, `preemptible`, `labels`, `taints`, `spot`"))
+    )]
+    #[trace]
+    fn test_validate(#[case] config: &str, #[case] expected: Result<(), String>) {
+        let config = format!(
+            "project: project\nlocation: location\ncluster: cluster\nnode_pool: node_pool\n{}",
+            config
+        );
+        let actual = Registry::default()
+            .build(
+                GKENodePoolConfigOperator::NAME,
+                serde_yaml::from_str(&config).unwrap(),
+            )
+            .unwrap()
+            .map(|_| ())
+            .map_err(|error| format!("{}", error));
+        assert_eq!(actual, expected);
+    }
+
+    // node pool detail is fetched only when spot is expected.
+    #[rstest]
+    #[case(
+        None,
+        0,
+        SpecResult::Failure{description: format!("node_pool has machine_type e2-standard-4, expected machine_type e2-standard-8 (retry count: 1)")}
+    )]
+    #[case(
+        Some(true),
+        1,
+        SpecResult::Failure{description: format!("node_pool has machine_type e2-standard-4, spot true, expected machine_type e2-standard-8 (retry count: 2)")}
+    )]
+    #[trace]
+    #[tokio::test]
+    async fn test_check(
+        #[case] spot: Option<bool>,
+        #[case] detail_fetches: usize,
+        #[case] expected: SpecResult,
+    ) {
+        let mut client = MockGKEClientTrait::new();
+        client.expect_fetch_node_pool().returning(|_, _, _, _| {
+            Ok(Fetched::new(
+                NodePool {
+                    config: Some(config()),
+                    ..Default::default()
+                },
+                1,
+            ))
+        });
+        client
+            .expect_fetch_node_pool_detail()
+            .times(detail_fetches)
+            .returning(|_, _, _, _| {
+                Ok(Fetched::new(
+                    NodePoolDetail {
+                        config: NodeConfigDetail { spot: true },
+                        ..Default::default()
+                    },
+                    1,
+                ))
+            });
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let mut operator = GKENodePoolConfigOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            format!("node_pool"),
+        );
+        operator.machine_type = Some(format!("e2-standard-8"));
+        operator.spot = spot;
+
+        assert_eq!(operator.check(&client).await.unwrap(), expected);
+    }
+}
@@ -0,0 +1,225 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::client::gke_client::GKEClientTrait;
+use crate::operator::Operator;
+use crate::spec::range::Range;
+use crate::spec::result::SpecResult;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GKENodePoolSizeOperator {
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    // without node_pool, nodes of the whole cluster are counted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_pool: Option<String>,
+    pub node_count: Range,
+}
+
+impl GKENodePoolSizeOperator {
+    pub fn new(
+        project: String,
+        location: String,
+        cluster: String,
+        node_pool: Option<String>,
+        node_count: Range,
+    ) -> GKENodePoolSizeOperator {
+        GKENodePoolSizeOperator {
+            project,
+            location,
+            cluster,
+            node_pool,
+            node_count,
+        }
+    }
+
+    fn compare(&self, node_count: u64) -> SpecResult {
+        let name = self.node_pool.as_ref().unwrap_or(&self.cluster);
+        let description = format!("{} has {} nodes", name, node_count);
+        if self.node_count.contains(node_count) {
+            SpecResult::Success { description }
+        } else {
+            SpecResult::Failure {
+                description: format!("{}, expected {}", description, self.node_count),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Operator for GKENodePoolSizeOperator {
+    const NAME: &'static str = "GKENodePoolSize";
+
+    fn target(&self) -> String {
+        match &self.node_pool {
+            Some(node_pool) => format!(
+                "{}/{}/{}/{}",
+                self.project, self.location, self.cluster, node_pool
+            ),
+            None => format!("{}/{}/{}", self.project, self.location, self.cluster),
+        }
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        match &self.node_pool {
+            Some(node_pool) => {
+                let fetched = client
+                    .fetch_node_pool(&self.project, &self.location, &self.cluster, node_pool)
+                    .await?;
+                // node count of node pool is the target of creation or resize, so live nodes
+                // are counted in instance groups (one per zone) of node pool.
+                let mut retries = fetched.retries;
+                let mut node_count = 0;
+                for url in &fetched.value.instance_group_urls {
+                    let size = client
+                        .fetch_instance_group_size(url)
+                        .await
+                        // the cause is kept in the message, because report shows only the
+                        // outermost error.
+                        .map_err(|e| anyhow!("{} has unknown number of nodes: {}", node_pool, e))?;
+                    node_count += size.value;
+                    retries += size.retries;
+                }
+                Ok(self.compare(node_count).with_retries(retries))
+            }
+            None => {
+                let fetched = client
+                    .fetch_cluster(&self.project, &self.location, &self.cluster)
+                    .await?;
+                // current_node_count is deprecated, but is the only live count of cluster nodes.
+                #[allow(deprecated)]
+                let node_count = fetched.value.current_node_count.max(0) as u64;
+                Ok(self.compare(node_count).with_retries(fetched.retries))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_node_pool_size_operator::*;
+    use googapis::google::container::v1::{Cluster, NodePool};
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        Some(format!("node_pool")),
+        Range { min: Some(3), max: None },
+        3,
+        SpecResult::Success{description: format!("node_pool has 3 nodes")}
+    )]
+    #[case(
+        Some(format!("node_pool")),
+        Range { min: Some(3), max: None },
+        2,
+        SpecResult::Failure{description: format!("node_pool has 2 nodes, expected at least 3")}
+    )]
+    #[case(
+        None,
+        Range { min: Some(6), max: Some(6) },
+        9,
+        SpecResult::Failure{description: format!("cluster has 9 nodes, expected 6")}
+    )]
+    #[trace]
+    fn test_compare(
+        #[case] node_pool: Option<String>,
+        #[case] node_count: Range,
+        #[case] actual: u64,
+        #[case] expected: SpecResult,
+    ) {
+        let operator = GKENodePoolSizeOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            node_pool,
+            node_count,
+        );
+
+        assert_eq!(operator.compare(actual), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Some(format!("node_pool")),
+        vec![format!("zone-a"), format!("zone-b"), format!("zone-c")],
+        Ok(SpecResult::Success{description: format!("node_pool has 6 nodes (retry count: 1)")})
+    )]
+    #[case(
+        Some(format!("node_pool")),
+        vec![],
+        Ok(SpecResult::Failure{description: format!("node_pool has 0 nodes, expected between 3 and 6")})
+    )]
+    #[case(
+        Some(format!("node_pool")),
+        vec![format!("zone-a"), format!("zone-x")],
+        Err(format!("node_pool has unknown number of nodes: instance group not found"))
+    )]
+    #[case(
+        None,
+        vec![],
+        Ok(SpecResult::Failure{description: format!("cluster has 12 nodes, expected between 3 and 6 (retry count: 1)")})
+    )]
+    #[trace]
+    #[tokio::test]
+    async fn test_check(
+        #[case] node_pool: Option<String>,
+        #[case] instance_group_urls: Vec<String>,
+        #[case] expected: Result<SpecResult, String>,
+    ) {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_node_pool()
+            .returning(move |_, _, _, _| {
+                Ok(Fetched::new(
+                    NodePool {
+                        // initial node count is not the live count, so it is not used.
+                        initial_node_count: 3,
+                        instance_group_urls: instance_group_urls.clone(),
+                        ..Default::default()
+                    },
+                    0,
+                ))
+            });
+        client
+            .expect_fetch_instance_group_size()
+            .returning(|url| match url {
+                "zone-a" => Ok(Fetched::new(2, 1)),
+                "zone-b" | "zone-c" => Ok(Fetched::new(2, 0)),
+                _ => Err(anyhow::anyhow!("instance group not found")),
+            });
+        client.expect_fetch_cluster().returning(|_, _, _| {
+            #[allow(deprecated)]
+            Ok(Fetched::new(
+                Cluster {
+                    current_node_count: 12,
+                    ..Default::default()
+                },
+                1,
+            ))
+        });
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let operator = GKENodePoolSizeOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            node_pool,
+            Range {
+                min: Some(3),
+                max: Some(6),
+            },
+        );
+
+        assert_eq!(
+            operator.check(&client).await.map_err(|e| format!("{}", e)),
+            expected
+        );
+    }
+}
@@ -1,17 +1,23 @@
 use anyhow::Result;
-use googapis::google::container::v1::node_pool;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 use crate::client::gke_client::GKEClientTrait;
-use crate::spec::node_pool_status::NodePoolStatus;
+use crate::operator::Operator;
+use crate::spec::node_pool_status::{self, NodePoolStatus};
 use crate::spec::result::SpecResult;
+use crate::specfile::validation::{unknown_statuses, Diagnosis};
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct GKENodePoolStatusOperator {
-    project: String,
-    location: String,
-    cluster: String,
-    node_pool: String,
-    status: Vec<NodePoolStatus>,
-    client: Box<dyn GKEClientTrait>,
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    pub node_pool: String,
+    pub status: Vec<NodePoolStatus>,
 }
 
 impl GKENodePoolStatusOperator {
@@ -21,21 +27,41 @@ impl GKENodePoolStatusOperator {
         cluster: String,
         node_pool: String,
         status: Vec<NodePoolStatus>,
-        client: Box<dyn GKEClientTrait>,
     ) -> GKENodePoolStatusOperator {
         GKENodePoolStatusOperator {
-            project: project,
-            location: location,
-            cluster: cluster,
-            node_pool: node_pool,
-            status: status,
-            client: client,
+            project,
+            location,
+            cluster,
+            node_pool,
+            status,
         }
     }
 
-    pub async fn check(&self) -> Result<SpecResult> {
-        let status = self
-            .client
+    fn compare(&self, node_pool_status: NodePoolStatus) -> SpecResult {
+        let description = format!("{} is {}", self.node_pool, node_pool_status);
+        if self.status.contains(&node_pool_status) {
+            SpecResult::Success { description }
+        } else if let NodePoolStatus::Unknown(_) = node_pool_status {
+            SpecResult::Error { description }
+        } else {
+            SpecResult::Failure { description }
+        }
+    }
+}
+
+#[async_trait]
+impl Operator for GKENodePoolStatusOperator {
+    const NAME: &'static str = "GKENodePoolStatus";
+
+    fn target(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.project, self.location, self.cluster, self.node_pool
+        )
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let fetched = client
             .fetch_node_pool_status(
                 &self.project,
                 &self.location,
@@ -43,39 +69,18 @@ impl GKENodePoolStatusOperator {
                 &self.node_pool,
             )
             .await?;
-        self.compare(status)
-    }
-
-    fn compare(&self, status: node_pool::Status) -> Result<SpecResult> {
-        match status {
-            node_pool::Status::Unspecified => Ok(self.compare_with(NodePoolStatus::Unspecified)),
-            node_pool::Status::Provisioning => Ok(self.compare_with(NodePoolStatus::Provisioning)),
-            node_pool::Status::Running => Ok(self.compare_with(NodePoolStatus::Running)),
-            node_pool::Status::RunningWithError => {
-                Ok(self.compare_with(NodePoolStatus::RunningWithError))
-            }
-            node_pool::Status::Reconciling => Ok(self.compare_with(NodePoolStatus::Reconciling)),
-            node_pool::Status::Stopping => Ok(self.compare_with(NodePoolStatus::Stopping)),
-            node_pool::Status::Error => Ok(self.compare_with(NodePoolStatus::Error)),
-        }
+        Ok(self.compare(fetched.value).with_retries(fetched.retries))
     }
 
-    fn compare_with(&self, node_pool_status: NodePoolStatus) -> SpecResult {
-        if self.status.contains(&node_pool_status) {
-            SpecResult::Success {
-                description: format!("{} is {}", self.node_pool, node_pool_status),
-            }
-        } else {
-            SpecResult::Failure {
-                description: format!("{} is {}", self.node_pool, node_pool_status),
-            }
-        }
+    fn diagnose(spec: &mut serde_yaml::Value) -> Vec<Diagnosis> {
+        unknown_statuses::<NodePoolStatus>(spec, node_pool_status::VARIANTS)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::client::gke_client::*;
+    use crate::client::retry::*;
     use crate::operator::gke_node_pool_status_operator::*;
     use rstest::*;
 
@@ -83,56 +88,118 @@ mod tests {
     #[case(
         format!("node_pool-001"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Unspecified,
+        NodePoolStatus::Unspecified,
         SpecResult::Failure{description: format!("node_pool-001 is Unspecified")}
     )]
     #[case(
         format!("node_pool-002"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Provisioning,
+        NodePoolStatus::Provisioning,
         SpecResult::Success{description: format!("node_pool-002 is Provisioning")}
     )]
     #[case(
         format!("node_pool-003"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Running,
+        NodePoolStatus::Running,
         SpecResult::Success{description: format!("node_pool-003 is Running")}
     )]
     #[case(
         format!("node_pool-004"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::RunningWithError,
+        NodePoolStatus::RunningWithError,
         SpecResult::Failure{description: format!("node_pool-004 is RunningWithError")}
     )]
     #[case(
         format!("node_pool-005"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Reconciling,
+        NodePoolStatus::Reconciling,
         SpecResult::Failure{description: format!("node_pool-005 is Reconciling")}
     )]
     #[case(
         format!("node_pool-006"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Stopping,
+        NodePoolStatus::Stopping,
         SpecResult::Failure{description: format!("node_pool-006 is Stopping")}
     )]
     #[case(
         format!("node_pool-007"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Error,
+        NodePoolStatus::Error,
         SpecResult::Failure{description: format!("node_pool-007 is Error")}
     )]
+    #[case(
+        format!("node_pool-101"),
+        vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
+        NodePoolStatus::Unknown(99),
+        SpecResult::Error{description: format!("node_pool-101 is Unknown(99)")}
+    )]
+    #[case(
+        format!("node_pool-102"),
+        vec![NodePoolStatus::Running, NodePoolStatus::Unknown(99)],
+        NodePoolStatus::Unknown(99),
+        SpecResult::Success{description: format!("node_pool-102 is Unknown(99)")}
+    )]
     #[trace]
     async fn test_check(
         #[case] node_pool: String,
         #[case] node_pool_status: Vec<NodePoolStatus>,
-        #[case] mocked_status: node_pool::Status,
+        #[case] mocked_status: NodePoolStatus,
+        #[case] expected: SpecResult,
+    ) {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_node_pool_status()
+            .returning(move |_, _, _, _| Ok(Fetched::new(mocked_status.clone(), 0)));
+
+        let operator = GKENodePoolStatusOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            node_pool,
+            node_pool_status,
+        );
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        match operator.check(&client).await {
+            Ok(spec_result) => {
+                assert_eq!(spec_result, expected);
+            }
+            Err(_) => {
+                panic!("check gke node_pool status error")
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(
+        format!("node_pool-001"),
+        vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
+        Fetched::new(NodePoolStatus::Running, 0),
+        SpecResult::Success{description: format!("node_pool-001 is Running")}
+    )]
+    #[case(
+        format!("node_pool-002"),
+        vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
+        Fetched::new(NodePoolStatus::Running, 2),
+        SpecResult::Success{description: format!("node_pool-002 is Running (retry count: 2)")}
+    )]
+    #[case(
+        format!("node_pool-003"),
+        vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
+        Fetched::new(NodePoolStatus::Error, 1),
+        SpecResult::Failure{description: format!("node_pool-003 is Error (retry count: 1)")}
+    )]
+    #[trace]
+    async fn test_check_with_retries(
+        #[case] node_pool: String,
+        #[case] node_pool_status: Vec<NodePoolStatus>,
+        #[case] mocked_fetched: Fetched<NodePoolStatus>,
         #[case] expected: SpecResult,
     ) {
         let mut client = MockGKEClientTrait::new();
         client
             .expect_fetch_node_pool_status()
-            .returning(move |_, _, _, _| Ok(mocked_status));
+            .returning(move |_, _, _, _| Ok(mocked_fetched.clone()));
 
         let operator = GKENodePoolStatusOperator::new(
             format!("project"),
@@ -140,15 +207,15 @@ mod tests {
             format!("cluster"),
             node_pool,
             node_pool_status,
-            Box::new(client),
         );
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
 
-        match operator.check().await {
+        match operator.check(&client).await {
             Ok(spec_result) => {
                 assert_eq!(spec_result, expected);
             }
             Err(_) => {
-                assert!(false, "check gke node_pool status error")
+                panic!("check gke node_pool status error")
             }
         }
     }
@@ -157,50 +224,62 @@ mod tests {
     #[case(
         format!("node_pool-001"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Unspecified,
+        NodePoolStatus::Unspecified,
         SpecResult::Failure{description: format!("node_pool-001 is Unspecified")}
     )]
     #[case(
         format!("node_pool-002"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Provisioning,
+        NodePoolStatus::Provisioning,
         SpecResult::Success{description: format!("node_pool-002 is Provisioning")}
     )]
     #[case(
         format!("node_pool-003"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Running,
+        NodePoolStatus::Running,
         SpecResult::Success{description: format!("node_pool-003 is Running")}
     )]
     #[case(
         format!("node_pool-004"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::RunningWithError,
+        NodePoolStatus::RunningWithError,
         SpecResult::Failure{description: format!("node_pool-004 is RunningWithError")}
     )]
     #[case(
         format!("node_pool-005"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Reconciling,
+        NodePoolStatus::Reconciling,
         SpecResult::Failure{description: format!("node_pool-005 is Reconciling")}
     )]
     #[case(
         format!("node_pool-006"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Stopping,
+        NodePoolStatus::Stopping,
         SpecResult::Failure{description: format!("node_pool-006 is Stopping")}
     )]
     #[case(
         format!("node_pool-007"),
         vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        node_pool::Status::Error,
+        NodePoolStatus::Error,
         SpecResult::Failure{description: format!("node_pool-007 is Error")}
     )]
+    #[case(
+        format!("node_pool-101"),
+        vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
+        NodePoolStatus::Unknown(99),
+        SpecResult::Error{description: format!("node_pool-101 is Unknown(99)")}
+    )]
+    #[case(
+        format!("node_pool-102"),
+        vec![NodePoolStatus::Running, NodePoolStatus::Unknown(99)],
+        NodePoolStatus::Unknown(99),
+        SpecResult::Success{description: format!("node_pool-102 is Unknown(99)")}
+    )]
     #[trace]
     fn test_compare(
         #[case] node_pool: String,
         #[case] node_pool_status: Vec<NodePoolStatus>,
-        #[case] input_status: node_pool::Status,
+        #[case] input_status: NodePoolStatus,
         #[case] expected: SpecResult,
     ) {
         let operator = GKENodePoolStatusOperator::new(
@@ -209,16 +288,8 @@ mod tests {
             format!("cluster"),
             node_pool,
             node_pool_status,
-            Box::new(GKEClient::new()),
         );
 
-        match operator.compare(input_status) {
-            Ok(spec_result) => {
-                assert_eq!(spec_result, expected)
-            }
-            Err(_) => {
-                assert!(false, "compare gke node_pool status error")
-            }
-        }
+        assert_eq!(operator.compare(input_status), expected);
     }
 }
@@ -0,0 +1,253 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::client::gke_client::GKEClientTrait;
+use crate::operator::Operator;
+use crate::spec::result::SpecResult;
+use crate::spec::scalar::option_from_str_or;
+use crate::spec::version::{Version, VersionConstraint};
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GKENodePoolVersionOperator {
+    pub project: String,
+    pub location: String,
+    pub cluster: String,
+    pub node_pool: String,
+    pub version: VersionConstraint,
+    // maximum number of minor versions node pool may be behind master.
+    #[serde(
+        default,
+        deserialize_with = "option_from_str_or",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_minor_skew: Option<u64>,
+}
+
+impl GKENodePoolVersionOperator {
+    pub fn new(
+        project: String,
+        location: String,
+        cluster: String,
+        node_pool: String,
+        version: VersionConstraint,
+        max_minor_skew: Option<u64>,
+    ) -> GKENodePoolVersionOperator {
+        GKENodePoolVersionOperator {
+            project,
+            location,
+            cluster,
+            node_pool,
+            version,
+            max_minor_skew,
+        }
+    }
+
+    // compare checks node pool version, and its skew from master version if max_minor_skew is
+    // given. every mismatch is listed in failure description.
+    fn compare(&self, node_pool_version: &str, master_version: Option<&str>) -> SpecResult {
+        let version = match node_pool_version.parse::<Version>() {
+            Ok(version) => version,
+            Err(error) => {
+                return SpecResult::Error {
+                    description: format!("{} has {}", self.node_pool, error),
+                }
+            }
+        };
+
+        let mut mismatches = vec![];
+        if !self.version.matches(&version) {
+            mismatches.push(format!("expected {}", self.version));
+        }
+        if let (Some(max_minor_skew), Some(master_version)) = (self.max_minor_skew, master_version)
+        {
+            let master = match master_version.parse::<Version>() {
+                Ok(master) => master,
+                Err(error) => {
+                    return SpecResult::Error {
+                        description: format!("{} has {}", self.cluster, error),
+                    }
+                }
+            };
+            let minor = version.minor().unwrap_or(0);
+            let master_minor = master.minor().unwrap_or(0);
+            if version.major() != master.major() {
+                mismatches.push(format!("major version differs from master {}", master));
+            } else if minor > master_minor {
+                mismatches.push(format!("newer than master {}", master));
+            } else if master_minor - minor > max_minor_skew {
+                mismatches.push(format!(
+                    "{} minor versions behind master {}, expected at most {}",
+                    master_minor - minor,
+                    master,
+                    max_minor_skew
+                ));
+            }
+        }
+
+        let description = format!("{} is {}", self.node_pool, version);
+        if mismatches.is_empty() {
+            SpecResult::Success { description }
+        } else {
+            SpecResult::Failure {
+                description: format!("{}, {}", description, mismatches.join(", ")),
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Operator for GKENodePoolVersionOperator {
+    const NAME: &'static str = "GKENodePoolVersion";
+
+    fn target(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.project, self.location, self.cluster, self.node_pool
+        )
+    }
+
+    async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let node_pool = client
+            .fetch_node_pool(
+                &self.project,
+                &self.location,
+                &self.cluster,
+                &self.node_pool,
+            )
+            .await?;
+        let mut retries = node_pool.retries;
+
+        // master version is fetched only for skew check.
+        let master_version = match self.max_minor_skew {
+            Some(_) => {
+                let cluster = client
+                    .fetch_cluster(&self.project, &self.location, &self.cluster)
+                    .await?;
+                retries += cluster.retries;
+                Some(cluster.value.current_master_version)
+            }
+            None => None,
+        };
+
+        Ok(self
+            .compare(&node_pool.value.version, master_version.as_deref())
+            .with_retries(retries))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_node_pool_version_operator::*;
+    use googapis::google::container::v1::{Cluster, NodePool};
+    use rstest::*;
+
+    #[rstest]
+    #[case(
+        ">=1.27",
+        None,
+        "1.27.3-gke.100",
+        None,
+        SpecResult::Success{description: format!("node_pool is 1.27.3-gke.100")}
+    )]
+    #[case(
+        ">=1.27",
+        Some(1),
+        "1.27.3-gke.100",
+        Some("1.28.2-gke.50"),
+        SpecResult::Success{description: format!("node_pool is 1.27.3-gke.100")}
+    )]
+    #[case(
+        ">=1.27",
+        Some(1),
+        "1.26.3-gke.100",
+        Some("1.28.2-gke.50"),
+        SpecResult::Failure{description: format!("node_pool is 1.26.3-gke.100, expected >=1.27, 2 minor versions behind master 1.28.2-gke.50, expected at most 1")}
+    )]
+    #[case(
+        ">=1.27",
+        Some(2),
+        "1.29.1-gke.1",
+        Some("1.28.2-gke.50"),
+        SpecResult::Failure{description: format!("node_pool is 1.29.1-gke.1, newer than master 1.28.2-gke.50")}
+    )]
+    #[case(
+        ">=1.27",
+        Some(2),
+        "unknown",
+        Some("1.28.2-gke.50"),
+        SpecResult::Error{description: format!("node_pool has invalid version: unknown")}
+    )]
+    #[trace]
+    fn test_compare(
+        #[case] constraint: &str,
+        #[case] max_minor_skew: Option<u64>,
+        #[case] node_pool_version: &str,
+        #[case] master_version: Option<&str>,
+        #[case] expected: SpecResult,
+    ) {
+        let operator = GKENodePoolVersionOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            format!("node_pool"),
+            constraint.parse().unwrap(),
+            max_minor_skew,
+        );
+
+        assert_eq!(
+            operator.compare(node_pool_version, master_version),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(None, SpecResult::Success{description: format!("node_pool is 1.26.3-gke.100 (retry count: 1)")})]
+    #[case(Some(1), SpecResult::Failure{description: format!("node_pool is 1.26.3-gke.100, 2 minor versions behind master 1.28.2-gke.50, expected at most 1 (retry count: 3)")})]
+    #[trace]
+    #[tokio::test]
+    async fn test_check(#[case] max_minor_skew: Option<u64>, #[case] expected: SpecResult) {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_node_pool()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok(Fetched::new(
+                    NodePool {
+                        version: format!("1.26.3-gke.100"),
+                        ..Default::default()
+                    },
+                    1,
+                ))
+            });
+        client
+            .expect_fetch_cluster()
+            .times(max_minor_skew.iter().count())
+            .returning(|_, _, _| {
+                Ok(Fetched::new(
+                    Cluster {
+                        current_master_version: format!("1.28.2-gke.50"),
+                        ..Default::default()
+                    },
+                    2,
+                ))
+            });
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let operator = GKENodePoolVersionOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("cluster"),
+            format!("node_pool"),
+            ">=1.26".parse().unwrap(),
+            max_minor_skew,
+        );
+
+        assert_eq!(operator.check(&client).await.unwrap(), expected);
+    }
+}
@@ -0,0 +1,343 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use serde::de::Error;
+use serde_json::json;
+use serde_yaml::Value;
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard};
+
+use crate::operator::gke_cluster_status_operator::GKEClusterStatusOperator;
+use crate::operator::gke_cluster_version_operator::GKEClusterVersionOperator;
+use crate::operator::gke_node_pool_autoscaling_operator::GKENodePoolAutoscalingOperator;
+use crate::operator::gke_node_pool_config_operator::GKENodePoolConfigOperator;
+use crate::operator::gke_node_pool_size_operator::GKENodePoolSizeOperator;
+use crate::operator::gke_node_pool_status_operator::GKENodePoolStatusOperator;
+use crate::operator::gke_node_pool_version_operator::GKENodePoolVersionOperator;
+use crate::operator::{DynOperator, Operator};
+use crate::spec::COMMON_KEYS;
+use crate::specfile::location::Segment;
+use crate::specfile::validation::Diagnosis;
+
+struct Entry {
+    name: &'static str,
+    build: fn(Value) -> serde_yaml::Result<Arc<dyn DynOperator>>,
+    schema: fn(&mut SchemaGenerator) -> Schema,
+    fields: fn() -> Vec<String>,
+    diagnose: fn(&mut Value) -> Vec<Diagnosis>,
+    error_path: fn(Value) -> Vec<Segment>,
+}
+
+// Registry maps operator key of spec to the Operator checking it.
+// default registry has operators of yubi.
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        let mut registry = Registry::new();
+        registry.register::<GKEClusterStatusOperator>();
+        registry.register::<GKENodePoolStatusOperator>();
+        registry.register::<GKEClusterVersionOperator>();
+        registry.register::<GKENodePoolVersionOperator>();
+        registry.register::<GKENodePoolAutoscalingOperator>();
+        registry.register::<GKENodePoolSizeOperator>();
+        registry.register::<GKENodePoolConfigOperator>();
+        registry
+    }
+}
+
+impl Registry {
+    // new returns an empty registry.
+    pub fn new() -> Registry {
+        Registry { entries: vec![] }
+    }
+
+    // register adds operator O, which replaces an operator registered with the same name.
+    pub fn register<O>(&mut self)
+    where
+        O: Operator,
+    {
+        let entry = Entry {
+            name: O::NAME,
+            build: build::<O>,
+            schema: schema::<O>,
+            fields: fields::<O>,
+            diagnose: O::diagnose,
+            error_path: error_path::<O>,
+        };
+        match self.entries.iter_mut().find(|entry| entry.name == O::NAME) {
+            Some(registered) => *registered = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    // names of registered operators in registration order.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.entries.iter().map(|entry| entry.name).collect()
+    }
+
+    // build deserializes config into the operator named name.
+    // it returns None if the operator is not registered.
+    pub fn build(
+        &self,
+        name: &str,
+        config: Value,
+    ) -> Option<serde_yaml::Result<Arc<dyn DynOperator>>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| (entry.build)(config))
+    }
+
+    // fields are keys of config which the operator named name declares.
+    // it returns None if the operator is not registered.
+    pub fn fields(&self, name: &str) -> Option<Vec<String>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| (entry.fields)())
+    }
+
+    // error_path returns path of the key in config which the operator named name can not be
+    // deserialized from. it is empty if the error is not in a key, or there is no error.
+    pub fn error_path(&self, name: &str, config: Value) -> Vec<Segment> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| (entry.error_path)(config))
+            .unwrap_or_default()
+    }
+
+    // diagnose spec of the operator named name, see Operator::diagnose.
+    // nothing is diagnosed if the operator is not registered.
+    pub fn diagnose(&self, name: &str, spec: &mut Value) -> Vec<Diagnosis> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| (entry.diagnose)(spec))
+            .unwrap_or_default()
+    }
+
+    // schemas of registered operators, each of which includes operator key and allows common
+    // keys.
+    pub fn schemas(&self, generator: &mut SchemaGenerator) -> Vec<Schema> {
+        self.entries
+            .iter()
+            .map(|entry| (entry.schema)(generator))
+            .collect()
+    }
+}
+
+fn build<O>(config: Value) -> serde_yaml::Result<Arc<dyn DynOperator>>
+where
+    O: Operator,
+{
+    let operator = serde_yaml::from_value::<O>(config)?;
+    operator.validate().map_err(serde_yaml::Error::custom)?;
+    Ok(Arc::new(operator))
+}
+
+// fields are properties of the schema, which are keys of config after serde renames them.
+fn fields<O>() -> Vec<String>
+where
+    O: Operator,
+{
+    O::json_schema(&mut SchemaGenerator::default())
+        .into_object()
+        .object
+        .map(|object| object.properties.into_keys().collect())
+        .unwrap_or_default()
+}
+
+fn error_path<O>(config: Value) -> Vec<Segment>
+where
+    O: Operator,
+{
+    match serde_path_to_error::deserialize::<_, O>(config) {
+        Ok(_) => vec![],
+        Err(error) => error
+            .path()
+            .iter()
+            .map_while(|segment| match segment {
+                serde_path_to_error::Segment::Map { key } => Some(Segment::Key(key.clone())),
+                serde_path_to_error::Segment::Seq { index } => Some(Segment::Index(*index)),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+fn schema<O>(generator: &mut SchemaGenerator) -> Schema
+where
+    O: Operator,
+{
+    let mut schema = O::json_schema(generator).into_object();
+    let object = schema.object();
+    object.properties.insert(
+        format!("operator"),
+        serde_json::from_value(json!({ "type": "string", "enum": [O::NAME] })).unwrap(),
+    );
+    object.required.insert(format!("operator"));
+    // common keys are described by Spec, but operators deny unknown keys, so they must be
+    // allowed here too.
+    for key in COMMON_KEYS {
+        object
+            .properties
+            .insert(format!("{}", key), Schema::Bool(true));
+    }
+    Schema::Object(schema)
+}
+
+fn global() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::default()))
+}
+
+// register adds operator O to the registry used to deserialize specs.
+// it must be called before specfiles are loaded.
+pub fn register<O>()
+where
+    O: Operator,
+{
+    global().write().unwrap().register::<O>();
+}
+
+// registry returns the registry used to deserialize specs.
+// the guard must be dropped before registry is called again in the same thread, because a
+// second read lock may deadlock while register is waiting for the write lock.
+pub fn registry() -> RwLockReadGuard<'static, Registry> {
+    global().read().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::GKEClientTrait;
+    use crate::operator::registry::*;
+    use crate::spec::result::SpecResult;
+    use crate::specfile::location::Segment;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use rstest::*;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, JsonSchema)]
+    struct EchoOperator {
+        message: String,
+        #[serde(default)]
+        repeat: Vec<u32>,
+    }
+
+    #[async_trait]
+    impl Operator for EchoOperator {
+        const NAME: &'static str = "Echo";
+
+        fn target(&self) -> String {
+            format!("echo")
+        }
+
+        async fn check(&self, _client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+            Ok(SpecResult::Success {
+                description: self.message.clone(),
+            })
+        }
+
+        fn diagnose(spec: &mut Value) -> Vec<Diagnosis> {
+            match spec.get("message").and_then(Value::as_str) {
+                Some("") => {
+                    spec["message"] = Value::from("diagnosed");
+                    vec![(
+                        vec![Segment::Key(format!("message"))],
+                        format!("message is empty"),
+                    )]
+                }
+                _ => vec![],
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_default_names() {
+        assert_eq!(
+            Registry::default().names(),
+            vec![
+                "GKEClusterStatus",
+                "GKENodePoolStatus",
+                "GKEClusterVersion",
+                "GKENodePoolVersion",
+                "GKENodePoolAutoscaling",
+                "GKENodePoolSize",
+                "GKENodePoolConfig"
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_build() {
+        let mut registry = Registry::new();
+        registry.register::<EchoOperator>();
+        registry.register::<EchoOperator>();
+
+        let config = serde_yaml::from_str::<Value>("message: hello").unwrap();
+        let operator = registry.build("Echo", config).unwrap().unwrap();
+
+        assert_eq!(registry.names(), vec!["Echo"]);
+        assert_eq!(operator.name(), "Echo");
+        assert_eq!(operator.target(), format!("echo"));
+        assert_eq!(
+            serde_yaml::to_string(&operator.config().unwrap()).unwrap(),
+            format!("message: hello\nrepeat: []\n")
+        );
+        assert!(registry.build("GKEClusterStatus", Value::Null).is_none());
+    }
+
+    #[rstest]
+    #[case("Echo", "message: ''", "message: diagnosed", vec![(vec![Segment::Key(format!("message"))], format!("message is empty"))])]
+    #[case("Echo", "message: hello", "message: hello", vec![])]
+    #[case("Unknown", "message: ''", "message: ''", vec![])]
+    #[trace]
+    fn test_diagnose(
+        #[case] name: &str,
+        #[case] spec: &str,
+        #[case] expected_spec: &str,
+        #[case] expected: Vec<Diagnosis>,
+    ) {
+        let mut registry = Registry::new();
+        registry.register::<EchoOperator>();
+
+        let mut spec = serde_yaml::from_str::<Value>(spec).unwrap();
+        let diagnosed = registry.diagnose(name, &mut spec);
+
+        assert_eq!(diagnosed, expected);
+        assert_eq!(spec, serde_yaml::from_str::<Value>(expected_spec).unwrap());
+    }
+
+    #[rstest]
+    fn test_fields() {
+        let mut registry = Registry::new();
+        registry.register::<EchoOperator>();
+
+        assert_eq!(
+            registry.fields("Echo"),
+            Some(vec![format!("message"), format!("repeat")])
+        );
+        assert_eq!(registry.fields("Unknown"), None);
+    }
+
+    #[rstest]
+    #[case("message: hello", vec![])]
+    #[case("message: [hello]", vec![Segment::Key(format!("message"))])]
+    #[case(
+        "message: hello\nrepeat: [1, x]",
+        vec![Segment::Key(format!("repeat")), Segment::Index(1)]
+    )]
+    #[case("repeat: [1]", vec![])]
+    #[trace]
+    fn test_error_path(#[case] config: &str, #[case] expected: Vec<Segment>) {
+        let mut registry = Registry::new();
+        registry.register::<EchoOperator>();
+
+        let config = serde_yaml::from_str::<Value>(config).unwrap();
+        assert_eq!(registry.error_path("Echo", config), expected);
+    }
+}
@@ -1,8 +1,12 @@
 mod detail;
-mod record;
+pub mod exit_status;
+pub mod format;
+mod junit;
+pub mod record;
 mod summary;
 
 use crate::report::detail::ReportDetail;
+use crate::report::exit_status::ExitStatus;
 use crate::report::record::Record;
 use crate::report::summary::ReportSummary;
 use crate::spec::result::SpecResult;
@@ -10,6 +14,7 @@ use crate::spec::Spec;
 use anyhow::Result;
 use serde::ser::{Serialize, SerializeMap, Serializer};
 
+#[derive(Debug, Default)]
 pub struct Report {
     records: Vec<Record>,
 }
@@ -44,18 +49,32 @@ impl Report {
     }
 
     pub fn record_ng(&mut self, spec: Spec, error: anyhow::Error) {
-        self.records.push(Record::new(
-            spec,
-            SpecResult::Error {
-                description: format!("{}", error),
-            },
-        ));
+        self.records.push(Record::from_result(spec, Err(error)));
+    }
+
+    pub fn push(&mut self, record: Record) {
+        self.records.push(record);
+    }
+
+    pub fn records(&self) -> &[Record] {
+        &self.records
     }
 
     pub fn is_all_green(&self) -> bool {
         self.total_record_count() == self.success_record_count()
     }
 
+    // error takes precedence over failure, because errored specs are not checked actually.
+    pub fn exit_status(&self) -> ExitStatus {
+        if self.error_record_count() > 0 {
+            ExitStatus::Error
+        } else if self.failure_record_count() > 0 {
+            ExitStatus::Failure
+        } else {
+            ExitStatus::Success
+        }
+    }
+
     fn total_record_count(&self) -> usize {
         self.success_record_count() + self.failure_record_count() + self.error_record_count()
     }
@@ -63,36 +82,29 @@ impl Report {
     fn success_record_count(&self) -> usize {
         self.records
             .iter()
-            .filter(|record| match record.spec_result {
-                SpecResult::Success { description: _ } => true,
-                _ => false,
-            })
+            .filter(|record| matches!(record.spec_result, SpecResult::Success { .. }))
             .count()
     }
 
     fn failure_record_count(&self) -> usize {
         self.records
             .iter()
-            .filter(|record| match record.spec_result {
-                SpecResult::Failure { description: _ } => true,
-                _ => false,
-            })
+            .filter(|record| matches!(record.spec_result, SpecResult::Failure { .. }))
             .count()
     }
 
     fn error_record_count(&self) -> usize {
         self.records
             .iter()
-            .filter(|record| match record.spec_result {
-                SpecResult::Error { description: _ } => true,
-                _ => false,
-            })
+            .filter(|record| matches!(record.spec_result, SpecResult::Error { .. }))
             .count()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::operator::gke_node_pool_status_operator::*;
     use crate::report::*;
     use crate::spec::cluster_status::*;
     use crate::spec::node_pool_status::*;
@@ -106,7 +118,8 @@ mod tests {
         1,
         0,
         0,
-        true
+        true,
+        ExitStatus::Success
     )]
     #[case(
         SpecResult::Failure{description: format!("failure_description")},
@@ -114,7 +127,8 @@ mod tests {
         0,
         1,
         0,
-        false
+        false,
+        ExitStatus::Failure
     )]
     #[case(
         SpecResult::Error{description: format!("error_description")},
@@ -122,7 +136,8 @@ mod tests {
         0,
         0,
         1,
-        false
+        false,
+        ExitStatus::Error
     )]
     #[trace]
     fn test_record_ok(
@@ -132,13 +147,14 @@ mod tests {
         #[case] expected_failure_count: usize,
         #[case] expected_error_count: usize,
         #[case] expected_is_all_green: bool,
+        #[case] expected_exit_status: ExitStatus,
     ) {
-        let spec = Spec::GKEClusterStatus {
+        let spec = Spec::new(GKEClusterStatusOperator {
             project: format!("project"),
             location: format!("location"),
             cluster: format!("cluster"),
             status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        };
+        });
 
         let mut report = Report::new();
         report.record_ok(spec, spec_result);
@@ -148,16 +164,18 @@ mod tests {
         assert_eq!(report.failure_record_count(), expected_failure_count);
         assert_eq!(report.error_record_count(), expected_error_count);
         assert_eq!(report.is_all_green(), expected_is_all_green);
+        assert_eq!(report.exit_status(), expected_exit_status);
     }
 
     #[rstest]
     #[case(
-        anyhow::Error::new(std::io::Error::new(std::io::ErrorKind::Other, "error_message")),
+        anyhow::Error::new(std::io::Error::other("error_message")),
         1,
         0,
         0,
         1,
-        false
+        false,
+        ExitStatus::Error
     )]
     #[trace]
     fn test_record_ng(
@@ -167,13 +185,14 @@ mod tests {
         #[case] expected_failure_count: usize,
         #[case] expected_error_count: usize,
         #[case] expected_is_all_green: bool,
+        #[case] expected_exit_status: ExitStatus,
     ) {
-        let spec = Spec::GKEClusterStatus {
+        let spec = Spec::new(GKEClusterStatusOperator {
             project: format!("project"),
             location: format!("location"),
             cluster: format!("cluster"),
             status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        };
+        });
 
         let mut report = Report::new();
         report.record_ng(spec, error);
@@ -183,76 +202,77 @@ mod tests {
         assert_eq!(report.failure_record_count(), expected_failure_count);
         assert_eq!(report.error_record_count(), expected_error_count);
         assert_eq!(report.is_all_green(), expected_is_all_green);
+        assert_eq!(report.exit_status(), expected_exit_status);
     }
 
     #[fixture]
     fn fixture_records() -> Vec<Record> {
         let mut records = vec![];
         records.push(Record::new(
-            Spec::GKEClusterStatus {
+            Spec::new(GKEClusterStatusOperator {
                 project: format!("success_project"),
                 location: format!("success_location"),
                 cluster: format!("success_cluster"),
                 status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-            },
+            }),
             SpecResult::Success {
                 description: format!("success_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKEClusterStatus {
+            Spec::new(GKEClusterStatusOperator {
                 project: format!("failure_project"),
                 location: format!("failure_location"),
                 cluster: format!("failure_cluster"),
                 status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-            },
+            }),
             SpecResult::Failure {
                 description: format!("failure_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKEClusterStatus {
+            Spec::new(GKEClusterStatusOperator {
                 project: format!("error_project"),
                 location: format!("error_location"),
                 cluster: format!("error_cluster"),
                 status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-            },
+            }),
             SpecResult::Error {
                 description: format!("error_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKENodePoolStatus {
+            Spec::new(GKENodePoolStatusOperator {
                 project: format!("success_project"),
                 location: format!("success_location"),
                 cluster: format!("success_cluster"),
                 node_pool: format!("success_node_pool"),
                 status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-            },
+            }),
             SpecResult::Success {
                 description: format!("success_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKENodePoolStatus {
+            Spec::new(GKENodePoolStatusOperator {
                 project: format!("failure_project"),
                 location: format!("failure_location"),
                 cluster: format!("failure_cluster"),
                 node_pool: format!("failure_node_pool"),
                 status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-            },
+            }),
             SpecResult::Failure {
                 description: format!("failure_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKENodePoolStatus {
+            Spec::new(GKENodePoolStatusOperator {
                 project: format!("error_project"),
                 location: format!("error_location"),
                 cluster: format!("error_cluster"),
                 node_pool: format!("error_node_pool"),
                 status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-            },
+            }),
             SpecResult::Error {
                 description: format!("error_description"),
             },
@@ -368,4 +388,25 @@ detail:
         assert_eq!(report.is_all_green(), expected_is_all_green);
         assert_eq!(serde_yaml::to_string(&report).unwrap(), expected_report);
     }
+
+    #[rstest]
+    #[case(vec![], ExitStatus::Success)]
+    #[case(vec![0, 3], ExitStatus::Success)]
+    #[case(vec![0, 1, 4], ExitStatus::Failure)]
+    #[case(vec![0, 1, 2], ExitStatus::Error)]
+    #[case(vec![5], ExitStatus::Error)]
+    #[trace]
+    fn test_exit_status(
+        fixture_records: Vec<Record>,
+        #[case] indexes: Vec<usize>,
+        #[case] expected: ExitStatus,
+    ) {
+        let mut report = Report::new();
+        for index in indexes {
+            let record = fixture_records[index].clone();
+            report.record_ok(record.spec, record.spec_result);
+        }
+
+        assert_eq!(report.exit_status(), expected);
+    }
 }
@@ -18,10 +18,7 @@ impl Serialize for ReportDetail {
     {
         let mut seq = serializer.serialize_seq(Some(self.records.len()))?;
         for record in &self.records {
-            seq.serialize_element(&Record::new(
-                record.spec.clone(),
-                record.spec_result.clone(),
-            ))?;
+            seq.serialize_element(record)?;
         }
         seq.end()
     }
@@ -29,6 +26,8 @@ impl Serialize for ReportDetail {
 
 #[cfg(test)]
 mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::operator::gke_node_pool_status_operator::*;
     use crate::report::*;
     use crate::spec::cluster_status::*;
     use crate::spec::node_pool_status::*;
@@ -39,70 +38,70 @@ mod tests {
     fn fixture_records() -> Vec<Record> {
         let mut records = vec![];
         records.push(Record::new(
-            Spec::GKEClusterStatus {
+            Spec::new(GKEClusterStatusOperator {
                 project: format!("success_project"),
                 location: format!("success_location"),
                 cluster: format!("success_cluster"),
                 status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-            },
+            }),
             SpecResult::Success {
                 description: format!("success_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKEClusterStatus {
+            Spec::new(GKEClusterStatusOperator {
                 project: format!("failure_project"),
                 location: format!("failure_location"),
                 cluster: format!("failure_cluster"),
                 status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-            },
+            }),
             SpecResult::Failure {
                 description: format!("failure_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKEClusterStatus {
+            Spec::new(GKEClusterStatusOperator {
                 project: format!("error_project"),
                 location: format!("error_location"),
                 cluster: format!("error_cluster"),
                 status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-            },
+            }),
             SpecResult::Error {
                 description: format!("error_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKENodePoolStatus {
+            Spec::new(GKENodePoolStatusOperator {
                 project: format!("success_project"),
                 location: format!("success_location"),
                 cluster: format!("success_cluster"),
                 node_pool: format!("success_node_pool"),
                 status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-            },
+            }),
             SpecResult::Success {
                 description: format!("success_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKENodePoolStatus {
+            Spec::new(GKENodePoolStatusOperator {
                 project: format!("failure_project"),
                 location: format!("failure_location"),
                 cluster: format!("failure_cluster"),
                 node_pool: format!("failure_node_pool"),
                 status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-            },
+            }),
             SpecResult::Failure {
                 description: format!("failure_description"),
             },
         ));
         records.push(Record::new(
-            Spec::GKENodePoolStatus {
+            Spec::new(GKENodePoolStatusOperator {
                 project: format!("error_project"),
                 location: format!("error_location"),
                 cluster: format!("error_cluster"),
                 node_pool: format!("error_node_pool"),
                 status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-            },
+            }),
             SpecResult::Error {
                 description: format!("error_description"),
             },
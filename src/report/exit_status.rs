@@ -0,0 +1,34 @@
+// ExitStatus is the exit code of yubi. the values are stable, so wrapper scripts can branch on them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ExitStatus {
+    // all specs are success.
+    Success = 0,
+    // at least one spec is failure, and no spec is error.
+    Failure = 1,
+    // at least one spec is error, or yubi failed to check specs.
+    Error = 2,
+    // specfile or command line arguments are invalid, so no spec is checked.
+    InvalidSpecfile = 3,
+}
+
+impl ExitStatus {
+    pub fn code(&self) -> i32 {
+        *self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::exit_status::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(ExitStatus::Success, 0)]
+    #[case(ExitStatus::Failure, 1)]
+    #[case(ExitStatus::Error, 2)]
+    #[case(ExitStatus::InvalidSpecfile, 3)]
+    #[trace]
+    fn test_code(#[case] exit_status: ExitStatus, #[case] expected: i32) {
+        assert_eq!(exit_status.code(), expected);
+    }
+}
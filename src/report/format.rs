@@ -0,0 +1,121 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::report::junit;
+use crate::report::Report;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Yaml,
+    Json,
+    Junit,
+}
+
+impl Format {
+    pub fn render(&self, report: &Report) -> Result<String> {
+        match self {
+            Self::Yaml => Ok(serde_yaml::to_string(report)?),
+            Self::Json => Ok(serde_json::to_string_pretty(report)?),
+            Self::Junit => Ok(junit::render(report)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::report::format::*;
+    use crate::spec::cluster_status::*;
+    use crate::spec::result::*;
+    use crate::spec::*;
+    use rstest::*;
+
+    #[fixture]
+    fn fixture_report() -> Report {
+        let mut report = Report::new();
+        report.record_ok(
+            Spec::new(GKEClusterStatusOperator {
+                project: format!("success_project"),
+                location: format!("success_location"),
+                cluster: format!("success_cluster"),
+                status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
+            }),
+            SpecResult::Success {
+                description: format!("success_description"),
+            },
+        );
+        report
+    }
+
+    #[rstest]
+    #[case(
+        Format::Yaml,
+        format!(
+r#"summary:
+  total: 1
+  success: 1
+  failure: 0
+  error: 0
+detail:
+- spec:
+    operator: GKEClusterStatus
+    project: success_project
+    location: success_location
+    cluster: success_cluster
+    status:
+    - Provisioning
+    - Running
+  spec_result:
+    code: success
+    description: success_description
+"#
+        )
+    )]
+    #[case(
+        Format::Json,
+        format!(
+r#"{{
+  "summary": {{
+    "total": 1,
+    "success": 1,
+    "failure": 0,
+    "error": 0
+  }},
+  "detail": [
+    {{
+      "spec": {{
+        "operator": "GKEClusterStatus",
+        "project": "success_project",
+        "location": "success_location",
+        "cluster": "success_cluster",
+        "status": [
+          "Provisioning",
+          "Running"
+        ]
+      }},
+      "spec_result": {{
+        "code": "success",
+        "description": "success_description"
+      }}
+    }}
+  ]
+}}"#
+        )
+    )]
+    #[trace]
+    fn test_render(fixture_report: Report, #[case] format: Format, #[case] expected: String) {
+        assert_eq!(format.render(&fixture_report).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(format!("yaml"), Format::Yaml)]
+    #[case(format!("json"), Format::Json)]
+    #[case(format!("junit"), Format::Junit)]
+    #[trace]
+    fn test_deserialize(#[case] input: String, #[case] expected: Format) {
+        assert_eq!(serde_yaml::from_str::<Format>(&input).unwrap(), expected);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::report::record::Record;
+use crate::report::Report;
+use crate::spec::result::SpecResult;
+
+// render report as JUnit XML. each record is a testcase named after its operator and target.
+pub fn render(report: &Report) -> String {
+    let tests = report.total_record_count();
+    let failures = report.failure_record_count();
+    let errors = report.error_record_count();
+
+    let mut xml = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        tests, failures, errors
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"yubi\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        tests, failures, errors
+    ));
+    for record in &report.records {
+        xml.push_str(&testcase(record));
+    }
+    xml.push_str("  </testsuite>\n");
+    xml.push_str("</testsuites>");
+    xml
+}
+
+fn testcase(record: &Record) -> String {
+    let open = format!(
+        "    <testcase classname=\"{}\" name=\"{}\"",
+        escape(record.spec.operator.name()),
+        escape(&record.spec.operator.target())
+    );
+    match &record.spec_result {
+        SpecResult::Success { .. } => format!("{}/>\n", open),
+        SpecResult::Failure { description } => format!(
+            "{}>\n      <failure message=\"{}\"/>\n    </testcase>\n",
+            open,
+            escape(description)
+        ),
+        SpecResult::Error { description } => format!(
+            "{}>\n      <error message=\"{}\"/>\n    </testcase>\n",
+            open,
+            escape(description)
+        ),
+    }
+}
+
+// escape value of attribute. tab, newline and carriage return are kept as character references,
+// and other C0 control characters, which are not allowed in XML 1.0, are replaced with U+FFFD.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' => escaped.push_str("&#9;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            c if c < ' ' => escaped.push('\u{fffd}'),
+            '\u{fffe}' | '\u{ffff}' => escaped.push('\u{fffd}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::operator::gke_node_pool_status_operator::*;
+    use crate::report::junit::*;
+    use crate::spec::cluster_status::*;
+    use crate::spec::node_pool_status::*;
+    use crate::spec::*;
+    use rstest::*;
+
+    #[fixture]
+    fn fixture_report() -> Report {
+        let mut report = Report::new();
+        report.record_ok(
+            Spec::new(GKEClusterStatusOperator {
+                project: format!("success_project"),
+                location: format!("success_location"),
+                cluster: format!("success_cluster"),
+                status: vec![ClusterStatus::Running],
+            }),
+            SpecResult::Success {
+                description: format!("success_description"),
+            },
+        );
+        report.record_ok(
+            Spec::new(GKEClusterStatusOperator {
+                project: format!("failure_project"),
+                location: format!("failure_location"),
+                cluster: format!("failure_cluster"),
+                status: vec![ClusterStatus::Running],
+            }),
+            SpecResult::Failure {
+                description: format!("failure_cluster is Provisioning"),
+            },
+        );
+        report.record_ng(
+            Spec::new(GKENodePoolStatusOperator {
+                project: format!("error_project"),
+                location: format!("error_location"),
+                cluster: format!("error_cluster"),
+                node_pool: format!("error_node_pool"),
+                status: vec![NodePoolStatus::Running],
+            }),
+            anyhow::anyhow!("\"error_node_pool\" <not found>"),
+        );
+        report
+    }
+
+    #[rstest]
+    fn test_render(fixture_report: Report) {
+        assert_eq!(
+            render(&fixture_report),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" errors="1">
+  <testsuite name="yubi" tests="3" failures="1" errors="1">
+    <testcase classname="GKEClusterStatus" name="success_project/success_location/success_cluster"/>
+    <testcase classname="GKEClusterStatus" name="failure_project/failure_location/failure_cluster">
+      <failure message="failure_cluster is Provisioning"/>
+    </testcase>
+    <testcase classname="GKENodePoolStatus" name="error_project/error_location/error_cluster/error_node_pool">
+      <error message="&quot;error_node_pool&quot; &lt;not found&gt;"/>
+    </testcase>
+  </testsuite>
+</testsuites>"#
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("plain", format!("plain"))]
+    #[case("a & b", format!("a &amp; b"))]
+    #[case("<tag attr='v'>", format!("&lt;tag attr=&apos;v&apos;&gt;"))]
+    #[case("\"quoted\"\nnext", format!("&quot;quoted&quot;&#10;next"))]
+    #[case("a\tb\r\nc", format!("a&#9;b&#13;&#10;c"))]
+    #[case("nul\0bell\u{7}esc\u{1b}", format!("nul\u{fffd}bell\u{fffd}esc\u{fffd}"))]
+    #[case("del\u{7f} \u{fffe}", format!("del\u{7f} \u{fffd}"))]
+    #[trace]
+    fn test_escape(#[case] input: &str, #[case] expected: String) {
+        assert_eq!(escape(input), expected);
+    }
+}
@@ -6,11 +6,29 @@ use serde::ser::{Serialize, SerializeStruct, Serializer};
 pub struct Record {
     pub spec: Spec,
     pub spec_result: SpecResult,
+    pub attempts: Option<u32>,
 }
 
 impl Record {
     pub fn new(spec: Spec, spec_result: SpecResult) -> Record {
-        Record { spec, spec_result }
+        Record {
+            spec,
+            spec_result,
+            attempts: None,
+        }
+    }
+
+    // from_result records error of check as SpecResult::Error.
+    pub fn from_result(spec: Spec, result: anyhow::Result<SpecResult>) -> Record {
+        match result {
+            Ok(spec_result) => Record::new(spec, spec_result),
+            Err(error) => Record::new(
+                spec,
+                SpecResult::Error {
+                    description: format!("{}", error),
+                },
+            ),
+        }
     }
 }
 
@@ -19,29 +37,39 @@ impl Serialize for Record {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Record", 2)?;
+        let len = 2 + self.spec.source.iter().count() + self.attempts.iter().count();
+        let mut state = serializer.serialize_struct("Record", len)?;
         state.serialize_field("spec", &self.spec)?;
+        if let Some(source) = &self.spec.source {
+            state.serialize_field("source", source)?;
+        }
         state.serialize_field("spec_result", &self.spec_result)?;
+        if let Some(attempts) = self.attempts {
+            state.serialize_field("attempts", &attempts)?;
+        }
         state.end()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::operator::gke_node_pool_status_operator::*;
     use crate::report::*;
     use crate::spec::cluster_status::*;
     use crate::spec::node_pool_status::*;
+    use crate::spec::source::*;
     use crate::spec::*;
     use rstest::*;
 
     #[rstest]
     #[case(
-        Spec::GKEClusterStatus {
+        Spec::new(GKEClusterStatusOperator {
             project: format!("success_project"),
             location: format!("success_location"),
             cluster: format!("success_cluster"),
             status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        },
+        }),
         SpecResult::Success { description: format!("success_description") },
         format!(
 r#"spec:
@@ -59,12 +87,12 @@ spec_result:
         )
     )]
     #[case(
-        Spec::GKEClusterStatus {
+        Spec::new(GKEClusterStatusOperator {
             project: format!("failure_project"),
             location: format!("failure_location"),
             cluster: format!("failure_cluster"),
             status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        },
+        }),
         SpecResult::Failure { description: format!("failure_description") },
         format!(
 r#"spec:
@@ -82,12 +110,12 @@ spec_result:
         )
     )]
     #[case(
-        Spec::GKEClusterStatus {
+        Spec::new(GKEClusterStatusOperator {
             project: format!("error_project"),
             location: format!("error_location"),
             cluster: format!("error_cluster"),
             status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        },
+        }),
         SpecResult::Error { description: format!("error_description") },
         format!(
 r#"spec:
@@ -105,13 +133,13 @@ spec_result:
         )
     )]
     #[case(
-        Spec::GKENodePoolStatus {
+        Spec::new(GKENodePoolStatusOperator {
             project: format!("success_project"),
             location: format!("success_location"),
             cluster: format!("success_cluster"),
             node_pool: format!("success_node_pool"),
             status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        },
+        }),
         SpecResult::Success { description: format!("success_description") },
         format!(
 r#"spec:
@@ -130,13 +158,13 @@ spec_result:
         )
     )]
     #[case(
-        Spec::GKENodePoolStatus {
+        Spec::new(GKENodePoolStatusOperator {
             project: format!("failure_project"),
             location: format!("failure_location"),
             cluster: format!("failure_cluster"),
             node_pool: format!("failure_node_pool"),
             status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        },
+        }),
         SpecResult::Failure { description: format!("failure_description") },
         format!(
 r#"spec:
@@ -155,13 +183,13 @@ spec_result:
         )
     )]
     #[case(
-        Spec::GKENodePoolStatus {
+        Spec::new(GKENodePoolStatusOperator {
             project: format!("error_project"),
             location: format!("error_location"),
             cluster: format!("error_cluster"),
             node_pool: format!("error_node_pool"),
             status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        },
+        }),
         SpecResult::Error { description: format!("error_description") },
         format!(
 r#"spec:
@@ -188,4 +216,80 @@ spec_result:
         let record = Record::new(spec, spec_result);
         assert_eq!(serde_yaml::to_string(&record).unwrap(), expected);
     }
+
+    #[rstest]
+    #[case(
+        Spec::new(GKEClusterStatusOperator {
+            project: format!("success_project"),
+            location: format!("success_location"),
+            cluster: format!("success_cluster"),
+            status: vec![ClusterStatus::Running],
+        }),
+        SpecResult::Success { description: format!("success_description") },
+        3,
+        format!(
+r#"spec:
+  operator: GKEClusterStatus
+  project: success_project
+  location: success_location
+  cluster: success_cluster
+  status:
+  - Running
+spec_result:
+  code: success
+  description: success_description
+attempts: 3
+"#
+        )
+    )]
+    #[trace]
+    fn test_serialize_with_attempts(
+        #[case] spec: Spec,
+        #[case] spec_result: SpecResult,
+        #[case] attempts: u32,
+        #[case] expected: String,
+    ) {
+        let mut record = Record::new(spec, spec_result);
+        record.attempts = Some(attempts);
+        assert_eq!(serde_yaml::to_string(&record).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        Spec {
+            source: Some(Source::new(format!("teams/spec.yml"), 1)),
+            ..Spec::new(GKEClusterStatusOperator {
+                project: format!("success_project"),
+                location: format!("success_location"),
+                cluster: format!("success_cluster"),
+                status: vec![ClusterStatus::Running],
+            })
+        },
+        SpecResult::Success { description: format!("success_description") },
+        format!(
+r#"spec:
+  operator: GKEClusterStatus
+  project: success_project
+  location: success_location
+  cluster: success_cluster
+  status:
+  - Running
+source:
+  file: teams/spec.yml
+  index: 1
+spec_result:
+  code: success
+  description: success_description
+"#
+        )
+    )]
+    #[trace]
+    fn test_serialize_with_source(
+        #[case] spec: Spec,
+        #[case] spec_result: SpecResult,
+        #[case] expected: String,
+    ) {
+        let record = Record::new(spec, spec_result);
+        assert_eq!(serde_yaml::to_string(&record).unwrap(), expected);
+    }
 }
@@ -0,0 +1,520 @@
+use anyhow::{anyhow, Result};
+use futures::stream::{FuturesUnordered, Stream, StreamExt};
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+use crate::client::gke_client::{GKEClient, GKEClientTrait};
+use crate::report::record::Record;
+use crate::report::Report;
+use crate::spec::result::SpecResult;
+use crate::spec::Spec;
+use crate::specfile::loader::load;
+
+pub const DEFAULT_CONCURRENCY: usize = 4;
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    // maximum number of specs checked at the same time, DEFAULT_CONCURRENCY if not given.
+    pub concurrency: Option<NonZeroUsize>,
+    // timeout of each spec check, unless the spec sets its own timeout.
+    // in wait mode, deadline of the whole run.
+    pub timeout: Option<Duration>,
+    // re-check failing specs until all of them succeed or timeout passes.
+    pub wait: bool,
+    // interval between checks in wait mode.
+    pub interval: Duration,
+}
+
+impl Default for RunOptions {
+    fn default() -> RunOptions {
+        RunOptions {
+            concurrency: None,
+            timeout: None,
+            wait: false,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+}
+
+// Runner checks specs with a GKE client, which can be replaced by another GKEClientTrait.
+pub struct Runner {
+    client: Arc<dyn GKEClientTrait>,
+    options: RunOptions,
+}
+
+impl Default for Runner {
+    fn default() -> Runner {
+        Runner::new(Arc::new(GKEClient::default()), RunOptions::default())
+    }
+}
+
+impl Runner {
+    pub fn new(client: Arc<dyn GKEClientTrait>, options: RunOptions) -> Runner {
+        Runner { client, options }
+    }
+
+    // run checks specs, and returns report whose records are in the order of specs.
+    pub async fn run(&self, specs: Vec<Spec>) -> Report {
+        let mut records = self.checks(specs).collect::<Vec<_>>().await;
+        records.sort_by_key(|(index, _)| *index);
+
+        let mut report = Report::new();
+        for (_, record) in records {
+            report.push(record);
+        }
+        report
+    }
+
+    // run_specfiles loads specfiles matched by patterns and checks their specs.
+    // options of runner are preferred over settings of specfiles. client of runner decides the
+    // endpoint, so specfiles which set endpoint, plaintext, ca_certificate or compute_endpoint
+    // are rejected.
+    pub async fn run_specfiles(&self, patterns: &[String]) -> Result<Report> {
+        let specfile = load(patterns)?;
+        if specfile.settings.has_connection() {
+            return Err(anyhow!(
+                "endpoint, plaintext, ca_certificate and compute_endpoint of settings are not supported by runner, whose client decides the endpoint"
+            ));
+        }
+        let runner = Runner::new(
+            self.client.clone(),
+            RunOptions {
+                concurrency: self.options.concurrency.or(specfile.settings.concurrency),
+                timeout: self.options.timeout.or(specfile.settings.timeout),
+                ..self.options.clone()
+            },
+        );
+        Ok(runner.run(specfile.specs).await)
+    }
+
+    // stream checks specs, and yields records in the order they complete.
+    pub fn stream(&self, specs: Vec<Spec>) -> impl Stream<Item = Record> + '_ {
+        self.checks(specs).map(|(_, record)| record)
+    }
+
+    // checks returns checks of specs with their indexes. every spec is checked in its own
+    // future, and the semaphore limits specs checked at the same time.
+    fn checks(
+        &self,
+        specs: Vec<Spec>,
+    ) -> FuturesUnordered<impl Future<Output = (usize, Record)> + '_> {
+        let concurrency = self
+            .options
+            .concurrency
+            .map_or(DEFAULT_CONCURRENCY, NonZeroUsize::get);
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+        let deadline = match self.options.wait {
+            true => self.options.timeout.map(|timeout| Instant::now() + timeout),
+            false => None,
+        };
+        specs
+            .into_iter()
+            .enumerate()
+            .map(|(index, spec)| {
+                let semaphore = semaphore.clone();
+                async move { (index, self.check(spec, &semaphore, deadline).await) }
+            })
+            .collect()
+    }
+
+    async fn check(&self, spec: Spec, semaphore: &Semaphore, deadline: Option<Instant>) -> Record {
+        let mut attempts = 0;
+        loop {
+            let result = {
+                // semaphore is never closed.
+                let _permit = semaphore.acquire().await.unwrap();
+                match deadline {
+                    Some(deadline) => spec.check_before(&self.client, deadline).await,
+                    None => {
+                        spec.check_with_timeout(&self.client, self.options.timeout)
+                            .await
+                    }
+                }
+            };
+            attempts += 1;
+
+            let interval = self.options.interval;
+            let done = !self.options.wait
+                || matches!(result, Ok(SpecResult::Success { .. }))
+                || deadline.is_some_and(|deadline| Instant::now() + interval >= deadline);
+            if done {
+                let mut record = Record::from_result(spec, result);
+                if self.options.wait {
+                    record.attempts = Some(attempts);
+                }
+                return record;
+            }
+
+            log::info!(
+                "{} is not green yet, check again after {}",
+                spec.operator.target(),
+                humantime::format_duration(interval)
+            );
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::runner::*;
+    use crate::spec::cluster_status::*;
+    use crate::spec::node_pool_status::*;
+    use googapis::google::container::v1::{Cluster, NodePool};
+    use rstest::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn cluster_spec(cluster: &str) -> Spec {
+        Spec::new(GKEClusterStatusOperator::new(
+            format!("project"),
+            format!("location"),
+            format!("{}", cluster),
+            vec![ClusterStatus::Running],
+        ))
+    }
+
+    fn descriptions(records: &[Record]) -> Vec<String> {
+        records
+            .iter()
+            .map(|record| match &record.spec_result {
+                SpecResult::Success { description } => format!("success: {}", description),
+                SpecResult::Failure { description } => format!("failure: {}", description),
+                SpecResult::Error { description } => format!("error: {}", description),
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_run() {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster_status()
+            .returning(|_, _, cluster| match cluster {
+                "cluster-001" => Ok(Fetched::new(ClusterStatus::Running, 0)),
+                "cluster-002" => Ok(Fetched::new(ClusterStatus::Error, 0)),
+                _ => Err(anyhow::anyhow!("not found")),
+            });
+        let runner = Runner::new(Arc::new(client), RunOptions::default());
+
+        let report = runner
+            .run(vec![
+                cluster_spec("cluster-001"),
+                cluster_spec("cluster-002"),
+                cluster_spec("cluster-003"),
+            ])
+            .await;
+
+        assert_eq!(
+            descriptions(report.records()),
+            vec![
+                format!("success: cluster-001 is Running"),
+                format!("failure: cluster-002 is Error"),
+                format!("error: not found"),
+            ]
+        );
+        assert_eq!(report.records()[0].attempts, None);
+    }
+
+    // DelayedClient answers cluster status after a delay given by cluster name, like
+    // "cluster-30" after 30ms, and records the largest number of fetches in flight.
+    #[derive(Default)]
+    struct DelayedClient {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl GKEClientTrait for DelayedClient {
+        async fn fetch_cluster_status(
+            &self,
+            _project: &str,
+            _location: &str,
+            cluster: &str,
+        ) -> Result<Fetched<ClusterStatus>> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            let delay = cluster.trim_start_matches("cluster-").parse().unwrap();
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(Fetched::new(ClusterStatus::Running, 0))
+        }
+
+        async fn fetch_cluster(&self, _: &str, _: &str, _: &str) -> Result<Fetched<Cluster>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+
+        async fn fetch_node_pool_status(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: &str,
+        ) -> Result<Fetched<NodePoolStatus>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+
+        async fn fetch_node_pool(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: &str,
+        ) -> Result<Fetched<NodePool>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+
+        async fn fetch_node_pool_detail(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+            _: &str,
+        ) -> Result<Fetched<NodePoolDetail>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+
+        async fn fetch_instance_group_size(&self, _: &str) -> Result<Fetched<u64>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+
+        async fn list_clusters(&self, _: &str, _: &str) -> Result<Fetched<Vec<ClusterSummary>>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+
+        async fn list_node_pools(
+            &self,
+            _: &str,
+            _: &str,
+            _: &str,
+        ) -> Result<Fetched<Vec<NodePoolSummary>>> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+    }
+
+    #[rstest]
+    #[case(1, 1)]
+    #[case(2, 2)]
+    #[case(8, 4)]
+    #[trace]
+    #[tokio::test]
+    async fn test_run_concurrency(
+        #[case] concurrency: usize,
+        #[case] expected_max_in_flight: usize,
+    ) {
+        let client = Arc::new(DelayedClient::default());
+        let runner = Runner::new(
+            client.clone(),
+            RunOptions {
+                concurrency: NonZeroUsize::new(concurrency),
+                ..Default::default()
+            },
+        );
+
+        // earlier specs finish later, but report keeps spec order.
+        let report = runner
+            .run(vec![
+                cluster_spec("cluster-80"),
+                cluster_spec("cluster-60"),
+                cluster_spec("cluster-40"),
+                cluster_spec("cluster-20"),
+            ])
+            .await;
+
+        assert_eq!(
+            descriptions(report.records()),
+            vec![
+                format!("success: cluster-80 is Running"),
+                format!("success: cluster-60 is Running"),
+                format!("success: cluster-40 is Running"),
+                format!("success: cluster-20 is Running"),
+            ]
+        );
+        assert_eq!(
+            client.max_in_flight.load(Ordering::SeqCst),
+            expected_max_in_flight
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_run_wait() {
+        let checked = Arc::new(AtomicUsize::new(0));
+        let counter = checked.clone();
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster_status()
+            .returning(
+                move |_, _, _| match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Ok(Fetched::new(ClusterStatus::Provisioning, 0)),
+                    _ => Ok(Fetched::new(ClusterStatus::Running, 0)),
+                },
+            );
+        let runner = Runner::new(
+            Arc::new(client),
+            RunOptions {
+                timeout: Some(Duration::from_secs(10)),
+                wait: true,
+                interval: Duration::from_millis(10),
+                ..Default::default()
+            },
+        );
+
+        let report = runner.run(vec![cluster_spec("cluster-001")]).await;
+
+        assert_eq!(
+            descriptions(report.records()),
+            vec![format!("success: cluster-001 is Running")]
+        );
+        assert_eq!(report.records()[0].attempts, Some(3));
+        assert_eq!(checked.load(Ordering::SeqCst), 3);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_run_wait_each_spec() {
+        let checked = Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+        let counter = checked.clone();
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster_status()
+            .returning(move |_, _, cluster| {
+                let mut checked = counter.lock().unwrap();
+                let count = checked.entry(format!("{}", cluster)).or_insert(0);
+                *count += 1;
+                match (cluster, *count) {
+                    ("cluster-002", 1 | 2) => Ok(Fetched::new(ClusterStatus::Provisioning, 0)),
+                    ("cluster-003", 1) => Err(anyhow::anyhow!("unavailable")),
+                    _ => Ok(Fetched::new(ClusterStatus::Running, 0)),
+                }
+            });
+        let runner = Runner::new(
+            Arc::new(client),
+            RunOptions {
+                timeout: Some(Duration::from_secs(10)),
+                wait: true,
+                interval: Duration::from_millis(30),
+                ..Default::default()
+            },
+        );
+
+        let started = Instant::now();
+        let report = runner
+            .run(vec![
+                cluster_spec("cluster-001"),
+                cluster_spec("cluster-002"),
+                cluster_spec("cluster-003"),
+            ])
+            .await;
+
+        // green spec is not checked again, and failing or erroring specs are re-checked
+        // after interval.
+        assert_eq!(
+            descriptions(report.records()),
+            vec![
+                format!("success: cluster-001 is Running"),
+                format!("success: cluster-002 is Running"),
+                format!("success: cluster-003 is Running"),
+            ]
+        );
+        let attempts = report
+            .records()
+            .iter()
+            .map(|record| record.attempts)
+            .collect::<Vec<Option<u32>>>();
+        assert_eq!(attempts, vec![Some(1), Some(3), Some(2)]);
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_run_wait_deadline() {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster_status()
+            .returning(|_, _, _| Ok(Fetched::new(ClusterStatus::Provisioning, 0)));
+        let runner = Runner::new(
+            Arc::new(client),
+            RunOptions {
+                timeout: Some(Duration::from_millis(50)),
+                wait: true,
+                interval: Duration::from_millis(20),
+                ..Default::default()
+            },
+        );
+
+        let report = runner.run(vec![cluster_spec("cluster-001")]).await;
+
+        assert_eq!(
+            descriptions(report.records()),
+            vec![format!("failure: cluster-001 is Provisioning")]
+        );
+        assert!(report.records()[0].attempts.unwrap() >= 2);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_stream() {
+        let runner = Runner::new(Arc::new(DelayedClient::default()), RunOptions::default());
+
+        // records are yielded in the order specs complete, not in spec order.
+        let records = runner
+            .stream(vec![
+                cluster_spec("cluster-80"),
+                cluster_spec("cluster-20"),
+                cluster_spec("cluster-50"),
+            ])
+            .collect::<Vec<Record>>()
+            .await;
+
+        assert_eq!(
+            descriptions(&records),
+            vec![
+                format!("success: cluster-20 is Running"),
+                format!("success: cluster-50 is Running"),
+                format!("success: cluster-80 is Running"),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("concurrency: 2", Ok(vec![format!("success: cluster-10 is Running")]))]
+    #[case(
+        "endpoint: https://endpoint",
+        Err(format!("endpoint, plaintext, ca_certificate and compute_endpoint of settings are not supported by runner, whose client decides the endpoint"))
+    )]
+    #[trace]
+    #[tokio::test]
+    async fn test_run_specfiles(
+        #[case] settings: &str,
+        #[case] expected: Result<Vec<String>, String>,
+    ) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spec.yml");
+        std::fs::write(
+            &path,
+            format!(
+                "settings:\n  {}\nspecs:\n- operator: GKEClusterStatus\n  project: project\n  location: location\n  cluster: cluster-10\n  status: [Running]\n",
+                settings
+            ),
+        )
+        .unwrap();
+        let runner = Runner::new(Arc::new(DelayedClient::default()), RunOptions::default());
+
+        let report = runner.run_specfiles(&[format!("{}", path.display())]).await;
+
+        assert_eq!(
+            report
+                .map(|report| descriptions(report.records()))
+                .map_err(|error| format!("{}", error)),
+            expected
+        );
+    }
+}
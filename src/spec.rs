@@ -1,130 +1,268 @@
 pub mod cluster_status;
+pub mod filter;
 pub mod node_pool_status;
+pub mod range;
 pub mod result;
+pub mod scalar;
+pub mod source;
+pub mod version;
 
-use anyhow::Result;
-use serde::ser::{Serialize, SerializeMap, Serializer};
+use anyhow::{anyhow, Result};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{Deserializer, Error as _};
+use serde::ser::{Error as _, Serialize, SerializeMap, Serializer};
 use serde::Deserialize;
+use serde_json::json;
+use serde_yaml::{Mapping, Value};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::client::gke_client::GKEClient;
-use crate::operator::gke_cluster_status_operator::GKEClusterStatusOperator;
-use crate::operator::gke_node_pool_status_operator::GKENodePoolStatusOperator;
-use crate::spec::cluster_status::ClusterStatus;
-use crate::spec::node_pool_status::NodePoolStatus;
+use crate::client::gke_client::GKEClientTrait;
+use crate::operator::registry::registry;
+use crate::operator::{DynOperator, Operator};
 use crate::spec::result::SpecResult;
+use crate::spec::source::Source;
 
-#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Clone)]
-#[serde(tag = "operator")]
-pub enum Spec {
-    GKEClusterStatus {
-        project: String,
-        location: String,
-        cluster: String,
-        status: Vec<ClusterStatus>,
-    },
-    GKENodePoolStatus {
-        project: String,
-        location: String,
-        cluster: String,
-        node_pool: String,
-        status: Vec<NodePoolStatus>,
-    },
+// Spec is an operator with common keys of spec.
+#[derive(Debug, Clone)]
+pub struct Spec {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub operator: Arc<dyn DynOperator>,
+    pub tags: Vec<String>,
+    pub timeout: Option<Duration>,
+    pub source: Option<Source>,
 }
 
+impl PartialEq for Spec {
+    fn eq(&self, other: &Spec) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && *self.operator == *other.operator
+            && self.tags == other.tags
+            && self.timeout == other.timeout
+            && self.source == other.source
+    }
+}
+
+impl Eq for Spec {}
+
+// Common is the keys of spec which do not belong to operator.
+#[derive(Deserialize, JsonSchema)]
+struct Common {
+    #[serde(default)]
+    #[schemars(description = "Stable identifier of spec, selected by --id")]
+    id: Option<String>,
+    #[serde(default)]
+    #[schemars(description = "Human readable name of spec")]
+    name: Option<String>,
+    #[serde(default)]
+    #[schemars(description = "Tags of spec, selected by --tag and --skip-tag")]
+    tags: Vec<String>,
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(
+        with = "Option<String>",
+        description = "Timeout of spec (e.g. 30s, 5m), overrides --timeout"
+    )]
+    timeout: Option<Duration>,
+}
+
+pub(crate) const COMMON_KEYS: &[&str] = &["id", "name", "tags", "timeout"];
+
 impl Serialize for Spec {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        match self {
-            Self::GKEClusterStatus {
-                project,
-                location,
-                cluster,
-                status,
-            } => {
-                let mut map = serializer.serialize_map(Some(5))?;
-                map.serialize_entry("operator", "GKEClusterStatus")?;
-                map.serialize_entry("project", project)?;
-                map.serialize_entry("location", location)?;
-                map.serialize_entry("cluster", cluster)?;
-                map.serialize_entry("status", status)?;
-                map.end()
-            }
-            Self::GKENodePoolStatus {
-                project,
-                location,
-                cluster,
-                node_pool,
-                status,
-            } => {
-                let mut map = serializer.serialize_map(Some(6))?;
-                map.serialize_entry("operator", "GKENodePoolStatus")?;
-                map.serialize_entry("project", project)?;
-                map.serialize_entry("location", location)?;
-                map.serialize_entry("cluster", cluster)?;
-                map.serialize_entry("node_pool", node_pool)?;
-                map.serialize_entry("status", status)?;
-                map.end()
+        let config = match self.operator.config().map_err(S::Error::custom)? {
+            Value::Mapping(config) => config,
+            _ => {
+                return Err(S::Error::custom(
+                    "operator must be serialized into a mapping",
+                ))
             }
+        };
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(id) = &self.id {
+            map.serialize_entry("id", id)?;
         }
+        if let Some(name) = &self.name {
+            map.serialize_entry("name", name)?;
+        }
+        map.serialize_entry("operator", self.operator.name())?;
+        for (key, value) in &config {
+            map.serialize_entry(key, value)?;
+        }
+        if !self.tags.is_empty() {
+            map.serialize_entry("tags", &self.tags)?;
+        }
+        if let Some(timeout) = self.timeout {
+            map.serialize_entry(
+                "timeout",
+                &format!("{}", humantime::format_duration(timeout)),
+            )?;
+        }
+        map.end()
     }
 }
 
-impl Spec {
-    pub async fn check(&self) -> Result<SpecResult> {
-        match self {
-            Self::GKEClusterStatus {
-                project,
-                location,
-                cluster,
-                status,
-            } => {
-                GKEClusterStatusOperator::new(
-                    project.clone(),
-                    location.clone(),
-                    cluster.clone(),
-                    status.clone(),
-                    Box::new(GKEClient::new()),
-                )
-                .check()
-                .await
+impl<'de> Deserialize<'de> for Spec {
+    fn deserialize<D>(deserializer: D) -> Result<Spec, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut config = Mapping::deserialize(deserializer)?;
+        let name = match config.remove("operator") {
+            Some(Value::String(name)) => name,
+            Some(_) => return Err(D::Error::custom("operator must be a string")),
+            None => return Err(D::Error::missing_field("operator")),
+        };
+        let mut common = Mapping::new();
+        for key in COMMON_KEYS {
+            if let Some(value) = config.remove(*key) {
+                common.insert(Value::from(*key), value);
             }
-            Self::GKENodePoolStatus {
-                project,
-                location,
-                cluster,
-                node_pool,
-                status,
-            } => {
-                GKENodePoolStatusOperator::new(
-                    project.clone(),
-                    location.clone(),
-                    cluster.clone(),
-                    node_pool.clone(),
-                    status.clone(),
-                    Box::new(GKEClient::new()),
-                )
-                .check()
-                .await
+        }
+        let common =
+            serde_yaml::from_value::<Common>(Value::Mapping(common)).map_err(D::Error::custom)?;
+
+        let registry = registry();
+        let operator = match registry.build(&name, Value::Mapping(config)) {
+            Some(operator) => operator.map_err(D::Error::custom)?,
+            None => {
+                return Err(D::Error::custom(format!(
+                    "unknown operator: {}, expected one of {}",
+                    name,
+                    registry.names().join(", ")
+                )))
             }
+        };
+        Ok(Spec {
+            id: common.id,
+            name: common.name,
+            operator,
+            tags: common.tags,
+            timeout: common.timeout,
+            source: None,
+        })
+    }
+}
+
+impl JsonSchema for Spec {
+    fn schema_name() -> String {
+        format!("Spec")
+    }
+
+    // keys of Common and one of registered operators.
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let mut schema = Common::json_schema(generator).into_object();
+        schema.subschemas().one_of = Some(registry().schemas(generator));
+        Schema::Object(schema)
+    }
+}
+
+impl Spec {
+    pub fn new<O>(operator: O) -> Spec
+    where
+        O: Operator,
+    {
+        Spec {
+            id: None,
+            name: None,
+            operator: Arc::new(operator),
+            tags: vec![],
+            timeout: None,
+            source: None,
+        }
+    }
+
+    pub async fn check(&self, client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        self.operator.check(client).await
+    }
+
+    pub async fn check_with_timeout(
+        &self,
+        client: &Arc<dyn GKEClientTrait>,
+        default_timeout: Option<Duration>,
+    ) -> Result<SpecResult> {
+        match self.timeout.or(default_timeout) {
+            Some(timeout) => with_timeout(timeout, self.check(client)).await,
+            None => self.check(client).await,
         }
     }
+
+    pub async fn check_before(
+        &self,
+        client: &Arc<dyn GKEClientTrait>,
+        deadline: Instant,
+    ) -> Result<SpecResult> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let timeout = self
+            .timeout
+            .map_or(remaining, |timeout| timeout.min(remaining));
+        with_timeout(timeout, self.check(client)).await
+    }
+}
+
+// schema of status, which is one of variants or "Unknown(<number>)".
+pub(crate) fn status_schema(variants: &[&str]) -> Schema {
+    let known = variants
+        .iter()
+        .filter(|variant| !variant.contains('<'))
+        .collect::<Vec<_>>();
+    serde_json::from_value(json!({
+        "anyOf": [
+            { "type": "string", "enum": known },
+            { "type": "string", "pattern": "^Unknown\\(-?[0-9]+\\)$" },
+        ]
+    }))
+    .unwrap()
+}
+
+// parse status value like "Unknown(7)", which is returned for status unknown to yubi.
+pub(crate) fn parse_unknown_status(value: &str) -> Option<i32> {
+    value
+        .strip_prefix("Unknown(")?
+        .strip_suffix(')')?
+        .parse::<i32>()
+        .ok()
+}
+
+async fn with_timeout<F>(timeout: Duration, future: F) -> Result<SpecResult>
+where
+    F: Future<Output = Result<SpecResult>>,
+{
+    match tokio::time::timeout(timeout, future).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!(
+            "timed out after {}",
+            humantime::format_duration(timeout)
+        )),
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::operator::gke_node_pool_status_operator::*;
+    use crate::spec::cluster_status::*;
+    use crate::spec::node_pool_status::*;
     use crate::spec::*;
     use rstest::*;
 
     #[rstest]
     #[case(
-        Spec::GKEClusterStatus {
+        Spec::new(GKEClusterStatusOperator {
             project: format!("project-001"),
             location: format!("location-001"),
             cluster: format!("cluster-001"),
             status: vec![ClusterStatus::Provisioning],
-        },
+        }),
         format!(
 r#"operator: GKEClusterStatus
 project: project-001
@@ -136,12 +274,12 @@ status:
         )
     )]
     #[case(
-        Spec::GKEClusterStatus {
+        Spec::new(GKEClusterStatusOperator {
             project: format!("project-002"),
             location: format!("location-002"),
             cluster: format!("cluster-002"),
             status: vec![ClusterStatus::Provisioning, ClusterStatus::Running],
-        },
+        }),
         format!(
 r#"operator: GKEClusterStatus
 project: project-002
@@ -154,13 +292,13 @@ status:
         )
     )]
     #[case(
-        Spec::GKENodePoolStatus {
+        Spec::new(GKENodePoolStatusOperator {
             project: format!("project-001"),
             location: format!("location-001"),
             cluster: format!("cluster-001"),
             node_pool: format!("node_pool-001"),
             status: vec![NodePoolStatus::Provisioning],
-        },
+        }),
         format!(
 r#"operator: GKENodePoolStatus
 project: project-001
@@ -173,13 +311,13 @@ status:
         )
     )]
     #[case(
-        Spec::GKENodePoolStatus {
+        Spec::new(GKENodePoolStatusOperator {
             project: format!("project-002"),
             location: format!("location-002"),
             cluster: format!("cluster-002"),
             node_pool: format!("node_pool-002"),
             status: vec![NodePoolStatus::Provisioning, NodePoolStatus::Running],
-        },
+        }),
         format!(
 r#"operator: GKENodePoolStatus
 project: project-002
@@ -189,6 +327,54 @@ node_pool: node_pool-002
 status:
 - Provisioning
 - Running
+"#
+        )
+    )]
+    #[case(
+        Spec {
+            timeout: Some(Duration::from_secs(90)),
+            ..Spec::new(GKEClusterStatusOperator {
+                project: format!("project-003"),
+                location: format!("location-003"),
+                cluster: format!("cluster-003"),
+                status: vec![ClusterStatus::Running],
+            })
+        },
+        format!(
+r#"operator: GKEClusterStatus
+project: project-003
+location: location-003
+cluster: cluster-003
+status:
+- Running
+timeout: 1m 30s
+"#
+        )
+    )]
+    #[case(
+        Spec {
+            id: Some(format!("prod-cluster")),
+            name: Some(format!("production cluster is running")),
+            tags: vec![format!("prod"), format!("critical")],
+            ..Spec::new(GKEClusterStatusOperator {
+                project: format!("project-004"),
+                location: format!("location-004"),
+                cluster: format!("cluster-004"),
+                status: vec![ClusterStatus::Running],
+            })
+        },
+        format!(
+r#"id: prod-cluster
+name: production cluster is running
+operator: GKEClusterStatus
+project: project-004
+location: location-004
+cluster: cluster-004
+status:
+- Running
+tags:
+- prod
+- critical
 "#
         )
     )]
@@ -196,4 +382,178 @@ status:
     fn test_spec_serialize(#[case] spec: Spec, #[case] expected: String) {
         assert_eq!(serde_yaml::to_string(&spec).unwrap(), expected);
     }
+
+    #[rstest]
+    #[case(
+        format!(
+r#"operator: GKEClusterStatus
+project: project-001
+location: location-001
+cluster: cluster-001
+status:
+- Running
+"#
+        ),
+        None
+    )]
+    #[case(
+        format!(
+r#"operator: GKENodePoolStatus
+project: project-002
+location: location-002
+cluster: cluster-002
+node_pool: node_pool-002
+status:
+- Running
+timeout: 30s
+"#
+        ),
+        Some(Duration::from_secs(30))
+    )]
+    #[trace]
+    fn test_spec_deserialize_timeout(#[case] input: String, #[case] expected: Option<Duration>) {
+        let spec = serde_yaml::from_str::<Spec>(&input).unwrap();
+        assert_eq!(spec.timeout, expected);
+    }
+
+    #[rstest]
+    #[case(
+        "GKEClusterStatus",
+        "cluster: cluster-001\nstatus: [Running]\nclustr: cluster-002"
+    )]
+    #[case(
+        "GKENodePoolStatus",
+        "cluster: cluster-001\nnode_pool: node_pool-001\nstatus: [Running]\nclustr: cluster-002"
+    )]
+    #[case(
+        "GKEClusterVersion",
+        "cluster: cluster-001\nversion: '1.27'\nclustr: cluster-002"
+    )]
+    #[case(
+        "GKENodePoolVersion",
+        "cluster: cluster-001\nnode_pool: node_pool-001\nversion: '1.27'\nclustr: cluster-002"
+    )]
+    #[case(
+        "GKENodePoolSize",
+        "cluster: cluster-001\nnode_pool: node_pool-001\nnode_count: {min: 1}\nclustr: cluster-002"
+    )]
+    #[trace]
+    fn test_spec_deserialize_unknown_key(#[case] operator: &str, #[case] keys: &str) {
+        let input = format!(
+            "operator: {}\nproject: project-001\nlocation: location-001\n{}",
+            operator, keys
+        );
+
+        match serde_yaml::from_str::<Spec>(&input) {
+            Ok(_) => panic!("unknown key should be rejected"),
+            Err(error) => assert!(
+                format!("{}", error).starts_with("unknown field `clustr`"),
+                "{}",
+                error
+            ),
+        }
+    }
+
+    #[rstest]
+    #[case(
+        Spec::new(GKEClusterStatusOperator {
+            project: format!("project"),
+            location: format!("location"),
+            cluster: format!("cluster"),
+            status: vec![ClusterStatus::Running],
+        }),
+        "GKEClusterStatus",
+        format!("project/location/cluster")
+    )]
+    #[case(
+        Spec::new(GKENodePoolStatusOperator {
+            project: format!("project"),
+            location: format!("location"),
+            cluster: format!("cluster"),
+            node_pool: format!("node_pool"),
+            status: vec![NodePoolStatus::Running],
+        }),
+        "GKENodePoolStatus",
+        format!("project/location/cluster/node_pool")
+    )]
+    #[trace]
+    fn test_name_and_target(
+        #[case] spec: Spec,
+        #[case] expected_name: &str,
+        #[case] expected_target: String,
+    ) {
+        assert_eq!(spec.operator.name(), expected_name);
+        assert_eq!(spec.operator.target(), expected_target);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_check_shares_client() {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_fetch_cluster_status()
+            .times(1)
+            .returning(|_, _, _| Ok(Fetched::new(ClusterStatus::Running, 0)));
+        client
+            .expect_fetch_node_pool_status()
+            .times(1)
+            .returning(|_, _, _, _| Ok(Fetched::new(NodePoolStatus::Running, 0)));
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let specs = vec![
+            Spec::new(GKEClusterStatusOperator {
+                project: format!("project"),
+                location: format!("location"),
+                cluster: format!("cluster"),
+                status: vec![ClusterStatus::Running],
+            }),
+            Spec::new(GKENodePoolStatusOperator {
+                project: format!("project"),
+                location: format!("location"),
+                cluster: format!("cluster"),
+                node_pool: format!("node_pool"),
+                status: vec![NodePoolStatus::Running],
+            }),
+        ];
+
+        for spec in specs {
+            match spec.check(&client).await {
+                Ok(spec_result) => assert_eq!(spec_result.code(), format!("success")),
+                Err(_) => panic!("check spec error"),
+            }
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_with_timeout_elapsed() {
+        let result = with_timeout(
+            Duration::from_millis(10),
+            std::future::pending::<Result<SpecResult>>(),
+        )
+        .await;
+
+        match result {
+            Ok(_) => panic!("pending check should time out"),
+            Err(error) => assert_eq!(format!("{}", error), "timed out after 10ms"),
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_with_timeout_completed() {
+        let result = with_timeout(Duration::from_secs(10), async {
+            Ok(SpecResult::Success {
+                description: format!("success_description"),
+            })
+        })
+        .await;
+
+        assert_eq!(
+            result.unwrap(),
+            SpecResult::Success {
+                description: format!("success_description")
+            }
+        );
+    }
 }
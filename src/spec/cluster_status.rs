@@ -1,8 +1,13 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{self, Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
-use serde::Deserialize;
 use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Clone)]
+use crate::spec::{parse_unknown_status, status_schema};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum ClusterStatus {
     Unspecified,
     Provisioning,
@@ -11,6 +16,40 @@ pub enum ClusterStatus {
     Stopping,
     Error,
     Degraded,
+    Unknown(i32),
+}
+
+pub const VARIANTS: &[&str] = &[
+    "Unspecified",
+    "Provisioning",
+    "Running",
+    "Reconciling",
+    "Stopping",
+    "Error",
+    "Degraded",
+    "Unknown(<number>)",
+];
+
+impl<'de> Deserialize<'de> for ClusterStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        match value.as_str() {
+            "Unspecified" => Ok(Self::Unspecified),
+            "Provisioning" => Ok(Self::Provisioning),
+            "Running" => Ok(Self::Running),
+            "Reconciling" => Ok(Self::Reconciling),
+            "Stopping" => Ok(Self::Stopping),
+            "Error" => Ok(Self::Error),
+            "Degraded" => Ok(Self::Degraded),
+            _ => match parse_unknown_status(&value) {
+                Some(number) => Ok(Self::Unknown(number)),
+                None => Err(de::Error::unknown_variant(&value, VARIANTS)),
+            },
+        }
+    }
 }
 
 impl Serialize for ClusterStatus {
@@ -22,6 +61,16 @@ impl Serialize for ClusterStatus {
     }
 }
 
+impl JsonSchema for ClusterStatus {
+    fn schema_name() -> String {
+        format!("ClusterStatus")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        status_schema(VARIANTS)
+    }
+}
+
 impl fmt::Display for ClusterStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)?;
@@ -31,7 +80,7 @@ impl fmt::Display for ClusterStatus {
 
 #[cfg(test)]
 mod tests {
-    use crate::spec::*;
+    use crate::spec::cluster_status::*;
     use rstest::*;
 
     #[rstest]
@@ -81,6 +130,13 @@ r#"Error
         ClusterStatus::Degraded,
         format!(
 r#"Degraded
+"#
+        )
+    )]
+    #[case(
+        ClusterStatus::Unknown(99),
+        format!(
+r#"Unknown(99)
 "#
         )
     )]
@@ -91,4 +147,27 @@ r#"Degraded
     ) {
         assert_eq!(serde_yaml::to_string(&cluster_status).unwrap(), expected);
     }
+
+    #[rstest]
+    #[case(format!("Running"), Ok(ClusterStatus::Running))]
+    #[case(format!("Unknown(99)"), Ok(ClusterStatus::Unknown(99)))]
+    #[case(format!("Unknown(-1)"), Ok(ClusterStatus::Unknown(-1)))]
+    #[case(
+        format!("Runing"),
+        Err(format!("unknown variant `Runing`, expected one of `Unspecified`, `Provisioning`, `Running`, `Reconciling`, `Stopping`, `Error`, `Degraded`, `Unknown(<number>)`"))
+    )]
+    #[case(
+        format!("Unknown(x)"),
+        Err(format!("unknown variant `Unknown(x)`, expected one of `Unspecified`, `Provisioning`, `Running`, `Reconciling`, `Stopping`, `Error`, `Degraded`, `Unknown(<number>)`"))
+    )]
+    #[trace]
+    fn test_cluster_status_deserialize(
+        #[case] input: String,
+        #[case] expected: Result<ClusterStatus, String>,
+    ) {
+        assert_eq!(
+            serde_yaml::from_str::<ClusterStatus>(&input).map_err(|e| format!("{}", e)),
+            expected
+        );
+    }
 }
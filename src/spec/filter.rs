@@ -0,0 +1,125 @@
+use std::fmt;
+
+use crate::spec::Spec;
+
+// SpecFilter selects specs to check by id and tags.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct SpecFilter {
+    // spec with one of ids is selected, all specs are selected if empty.
+    pub ids: Vec<String>,
+    // spec with one of tags is selected, all specs are selected if empty.
+    pub tags: Vec<String>,
+    // spec with one of skip_tags is not selected, even if it is selected by ids or tags.
+    pub skip_tags: Vec<String>,
+}
+
+impl SpecFilter {
+    pub fn new(ids: Vec<String>, tags: Vec<String>, skip_tags: Vec<String>) -> SpecFilter {
+        SpecFilter {
+            ids,
+            tags,
+            skip_tags,
+        }
+    }
+
+    pub fn matches(&self, spec: &Spec) -> bool {
+        let id_matched =
+            self.ids.is_empty() || spec.id.as_ref().is_some_and(|id| self.ids.contains(id));
+        let tag_matched =
+            self.tags.is_empty() || spec.tags.iter().any(|tag| self.tags.contains(tag));
+        let skipped = spec.tags.iter().any(|tag| self.skip_tags.contains(tag));
+        id_matched && tag_matched && !skipped
+    }
+
+    // is_empty returns true if filter selects all specs.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty() && self.tags.is_empty() && self.skip_tags.is_empty()
+    }
+}
+
+// filter is shown as its command line options, like "--id a, --skip-tag slow".
+impl fmt::Display for SpecFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let options = self
+            .ids
+            .iter()
+            .map(|id| format!("--id {}", id))
+            .chain(self.tags.iter().map(|tag| format!("--tag {}", tag)))
+            .chain(
+                self.skip_tags
+                    .iter()
+                    .map(|tag| format!("--skip-tag {}", tag)),
+            )
+            .collect::<Vec<String>>();
+        write!(f, "{}", options.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::spec::cluster_status::*;
+    use crate::spec::filter::*;
+    use crate::spec::*;
+    use rstest::*;
+
+    fn spec(id: Option<&str>, tags: Vec<&str>) -> Spec {
+        Spec {
+            id: id.map(|id| format!("{}", id)),
+            tags: tags.into_iter().map(|tag| format!("{}", tag)).collect(),
+            ..Spec::new(GKEClusterStatusOperator {
+                project: format!("project"),
+                location: format!("location"),
+                cluster: format!("cluster"),
+                status: vec![ClusterStatus::Running],
+            })
+        }
+    }
+
+    fn strings(values: Vec<&str>) -> Vec<String> {
+        values
+            .into_iter()
+            .map(|value| format!("{}", value))
+            .collect()
+    }
+
+    #[rstest]
+    #[case(vec![], vec![], vec![], spec(None, vec![]), true)]
+    #[case(vec!["a"], vec![], vec![], spec(Some("a"), vec![]), true)]
+    #[case(vec!["a"], vec![], vec![], spec(Some("b"), vec![]), false)]
+    #[case(vec!["a"], vec![], vec![], spec(None, vec![]), false)]
+    #[case(vec![], vec!["prod"], vec![], spec(None, vec!["dev", "prod"]), true)]
+    #[case(vec![], vec!["prod"], vec![], spec(None, vec!["dev"]), false)]
+    #[case(vec![], vec![], vec!["slow"], spec(None, vec!["prod", "slow"]), false)]
+    #[case(vec![], vec![], vec!["slow"], spec(None, vec!["prod"]), true)]
+    #[case(vec![], vec!["prod"], vec!["slow"], spec(None, vec!["prod", "slow"]), false)]
+    #[case(vec!["a"], vec!["prod"], vec![], spec(Some("a"), vec!["dev"]), false)]
+    #[case(vec!["a", "b"], vec!["prod"], vec![], spec(Some("b"), vec!["prod"]), true)]
+    #[trace]
+    fn test_matches(
+        #[case] ids: Vec<&str>,
+        #[case] tags: Vec<&str>,
+        #[case] skip_tags: Vec<&str>,
+        #[case] spec: Spec,
+        #[case] expected: bool,
+    ) {
+        let filter = SpecFilter::new(strings(ids), strings(tags), strings(skip_tags));
+        assert_eq!(filter.matches(&spec), expected);
+    }
+
+    #[rstest]
+    #[case(vec![], vec![], vec![], "")]
+    #[case(vec!["a", "b"], vec![], vec![], "--id a, --id b")]
+    #[case(vec!["a"], vec!["prod"], vec!["slow"], "--id a, --tag prod, --skip-tag slow")]
+    #[trace]
+    fn test_display(
+        #[case] ids: Vec<&str>,
+        #[case] tags: Vec<&str>,
+        #[case] skip_tags: Vec<&str>,
+        #[case] expected: &str,
+    ) {
+        let filter = SpecFilter::new(strings(ids), strings(tags), strings(skip_tags));
+        assert_eq!(format!("{}", filter), expected);
+        assert_eq!(filter.is_empty(), expected.is_empty());
+    }
+}
@@ -1,8 +1,13 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{self, Deserialize, Deserializer};
 use serde::ser::{Serialize, Serializer};
-use serde::Deserialize;
 use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, Hash, Deserialize, Clone)]
+use crate::spec::{parse_unknown_status, status_schema};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum NodePoolStatus {
     Unspecified,
     Provisioning,
@@ -11,6 +16,40 @@ pub enum NodePoolStatus {
     Reconciling,
     Stopping,
     Error,
+    Unknown(i32),
+}
+
+pub const VARIANTS: &[&str] = &[
+    "Unspecified",
+    "Provisioning",
+    "Running",
+    "RunningWithError",
+    "Reconciling",
+    "Stopping",
+    "Error",
+    "Unknown(<number>)",
+];
+
+impl<'de> Deserialize<'de> for NodePoolStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        match value.as_str() {
+            "Unspecified" => Ok(Self::Unspecified),
+            "Provisioning" => Ok(Self::Provisioning),
+            "Running" => Ok(Self::Running),
+            "RunningWithError" => Ok(Self::RunningWithError),
+            "Reconciling" => Ok(Self::Reconciling),
+            "Stopping" => Ok(Self::Stopping),
+            "Error" => Ok(Self::Error),
+            _ => match parse_unknown_status(&value) {
+                Some(number) => Ok(Self::Unknown(number)),
+                None => Err(de::Error::unknown_variant(&value, VARIANTS)),
+            },
+        }
+    }
 }
 
 impl Serialize for NodePoolStatus {
@@ -22,6 +61,16 @@ impl Serialize for NodePoolStatus {
     }
 }
 
+impl JsonSchema for NodePoolStatus {
+    fn schema_name() -> String {
+        format!("NodePoolStatus")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        status_schema(VARIANTS)
+    }
+}
+
 impl fmt::Display for NodePoolStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)?;
@@ -81,6 +130,13 @@ r#"Stopping
         NodePoolStatus::Error,
         format!(
 r#"Error
+"#
+        )
+    )]
+    #[case(
+        NodePoolStatus::Unknown(99),
+        format!(
+r#"Unknown(99)
 "#
         )
     )]
@@ -91,4 +147,27 @@ r#"Error
     ) {
         assert_eq!(serde_yaml::to_string(&node_pool_status).unwrap(), expected);
     }
+
+    #[rstest]
+    #[case(format!("Running"), Ok(NodePoolStatus::Running))]
+    #[case(format!("Unknown(99)"), Ok(NodePoolStatus::Unknown(99)))]
+    #[case(format!("Unknown(-1)"), Ok(NodePoolStatus::Unknown(-1)))]
+    #[case(
+        format!("Runing"),
+        Err(format!("unknown variant `Runing`, expected one of `Unspecified`, `Provisioning`, `Running`, `RunningWithError`, `Reconciling`, `Stopping`, `Error`, `Unknown(<number>)`"))
+    )]
+    #[case(
+        format!("Unknown(x)"),
+        Err(format!("unknown variant `Unknown(x)`, expected one of `Unspecified`, `Provisioning`, `Running`, `RunningWithError`, `Reconciling`, `Stopping`, `Error`, `Unknown(<number>)`"))
+    )]
+    #[trace]
+    fn test_node_pool_status_deserialize(
+        #[case] input: String,
+        #[case] expected: Result<NodePoolStatus, String>,
+    ) {
+        assert_eq!(
+            serde_yaml::from_str::<NodePoolStatus>(&input).map_err(|e| format!("{}", e)),
+            expected
+        );
+    }
 }
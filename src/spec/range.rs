@@ -0,0 +1,178 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::json;
+use std::fmt;
+
+use crate::spec::scalar::{from_str_or, option_from_str_or};
+
+// Range is bounds of a count like number of nodes. it is given as a number for an exact count,
+// or as a mapping with optional min and max, both inclusive.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Range {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+}
+
+impl Range {
+    pub fn contains(&self, value: u64) -> bool {
+        self.min.is_none_or(|min| min <= value) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => write!(f, "{}", min),
+            (Some(min), Some(max)) => write!(f, "between {} and {}", min, max),
+            (Some(min), None) => write!(f, "at least {}", min),
+            (None, Some(max)) => write!(f, "at most {}", max),
+            (None, None) => write!(f, "any"),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum RawRange {
+    #[serde(deserialize_with = "from_str_or")]
+    Exact(u64),
+    Bounds {
+        #[serde(default, deserialize_with = "option_from_str_or")]
+        min: Option<u64>,
+        #[serde(default, deserialize_with = "option_from_str_or")]
+        max: Option<u64>,
+    },
+}
+
+impl<'de> Deserialize<'de> for Range {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let range = match RawRange::deserialize(deserializer) {
+            Ok(RawRange::Exact(value)) => Range {
+                min: Some(value),
+                max: Some(value),
+            },
+            Ok(RawRange::Bounds { min, max }) => Range { min, max },
+            Err(_) => {
+                return Err(de::Error::custom(
+                    "range must be a number or a mapping with min and max",
+                ))
+            }
+        };
+        match (range.min, range.max) {
+            // an empty mapping would match any count, which is likely a mistake.
+            (None, None) => Err(de::Error::custom("range must have min or max")),
+            (Some(min), Some(max)) if min > max => Err(de::Error::custom(format!(
+                "min {} of range is greater than max {}",
+                min, max
+            ))),
+            _ => Ok(range),
+        }
+    }
+}
+
+impl Serialize for Range {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if min == max => serializer.serialize_u64(min),
+            _ => {
+                let len = self.min.iter().count() + self.max.iter().count();
+                let mut map = serializer.serialize_map(Some(len))?;
+                if let Some(min) = self.min {
+                    map.serialize_entry("min", &min)?;
+                }
+                if let Some(max) = self.max {
+                    map.serialize_entry("max", &max)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+impl JsonSchema for Range {
+    fn schema_name() -> String {
+        format!("Range")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "oneOf": [
+                { "type": "integer", "minimum": 0 },
+                {
+                    "type": "object",
+                    "properties": {
+                        "min": { "type": "integer", "minimum": 0 },
+                        "max": { "type": "integer", "minimum": 0 },
+                    },
+                    "additionalProperties": false,
+                    "minProperties": 1,
+                },
+            ]
+        }))
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spec::range::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("3", Ok((Some(3), Some(3))), "3")]
+    #[case("min: 1\nmax: 5", Ok((Some(1), Some(5))), "between 1 and 5")]
+    #[case("min: 1", Ok((Some(1), None)), "at least 1")]
+    #[case("max: 5", Ok((None, Some(5))), "at most 5")]
+    #[case("min: 5\nmax: 1", Err(format!("min 5 of range is greater than max 1")), "")]
+    #[case("-1", Err(format!("range must be a number or a mapping with min and max")), "")]
+    #[case("minimum: 3", Err(format!("range must be a number or a mapping with min and max")), "")]
+    #[case("min: 1\nmx: 5", Err(format!("range must be a number or a mapping with min and max")), "")]
+    #[case("{}", Err(format!("range must have min or max")), "")]
+    #[case("'three'", Err(format!("range must be a number or a mapping with min and max")), "")]
+    #[trace]
+    fn test_deserialize(
+        #[case] input: &str,
+        #[case] expected: Result<(Option<u64>, Option<u64>), String>,
+        #[case] expected_display: &str,
+    ) {
+        let actual = serde_yaml::from_str::<Range>(input);
+        match (actual, expected) {
+            (Ok(range), Ok((min, max))) => {
+                assert_eq!(range, Range { min, max });
+                assert_eq!(format!("{}", range), expected_display);
+                assert_eq!(serde_yaml::to_string(&range).unwrap().trim_end(), input);
+            }
+            (Err(error), Err(expected)) => assert_eq!(format!("{}", error), expected),
+            (actual, expected) => panic!("{:?} is not {:?}", actual, expected),
+        }
+    }
+
+    // interpolated variables are strings, so numbers in strings are accepted too.
+    #[rstest]
+    #[case("'3'", Range { min: Some(3), max: Some(3) })]
+    #[case("min: '1'\nmax: 5", Range { min: Some(1), max: Some(5) })]
+    #[trace]
+    fn test_deserialize_string(#[case] input: &str, #[case] expected: Range) {
+        assert_eq!(serde_yaml::from_str::<Range>(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(Range { min: Some(1), max: Some(3) }, 0, false)]
+    #[case(Range { min: Some(1), max: Some(3) }, 1, true)]
+    #[case(Range { min: Some(1), max: Some(3) }, 3, true)]
+    #[case(Range { min: Some(1), max: Some(3) }, 4, false)]
+    #[case(Range { min: None, max: None }, 100, true)]
+    #[trace]
+    fn test_contains(#[case] range: Range, #[case] value: u64, #[case] expected: bool) {
+        assert_eq!(range.contains(value), expected);
+    }
+}
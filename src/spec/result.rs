@@ -49,6 +49,23 @@ impl SpecResult {
             }
         }
     }
+
+    pub fn with_retries(self, retries: u32) -> SpecResult {
+        if retries == 0 {
+            return self;
+        }
+        match self {
+            Self::Success { description } => Self::Success {
+                description: format!("{} (retry count: {})", description, retries),
+            },
+            Self::Failure { description } => Self::Failure {
+                description: format!("{} (retry count: {})", description, retries),
+            },
+            Self::Error { description } => Self::Error {
+                description: format!("{} (retry count: {})", description, retries),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -74,6 +91,36 @@ mod tests {
         assert_eq!(spec_result.code(), expected);
     }
 
+    #[rstest]
+    #[case(
+        SpecResult::Success{ description: format!("success_description")},
+        0,
+        SpecResult::Success{ description: format!("success_description")}
+    )]
+    #[case(
+        SpecResult::Success{ description: format!("success_description")},
+        2,
+        SpecResult::Success{ description: format!("success_description (retry count: 2)")}
+    )]
+    #[case(
+        SpecResult::Failure{ description: format!("failure_description")},
+        1,
+        SpecResult::Failure{ description: format!("failure_description (retry count: 1)")}
+    )]
+    #[case(
+        SpecResult::Error{ description: format!("error_description")},
+        3,
+        SpecResult::Error{ description: format!("error_description (retry count: 3)")}
+    )]
+    #[trace]
+    fn test_with_retries(
+        #[case] spec_result: SpecResult,
+        #[case] retries: u32,
+        #[case] expected: SpecResult,
+    ) {
+        assert_eq!(spec_result.with_retries(retries), expected);
+    }
+
     #[rstest]
     #[case(
         SpecResult::Success{ description: format!("success_description")},
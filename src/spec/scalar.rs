@@ -0,0 +1,66 @@
+use serde::de::{Deserialize, DeserializeOwned, Deserializer, Error};
+use serde_yaml::Value;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// from_str_or deserializes a number or a boolean, which may also be given as a string like "3",
+// because interpolated variables are always strings.
+pub(crate) fn from_str_or<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + FromStr,
+    T::Err: Display,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(value) => value
+            .parse::<T>()
+            .map_err(|error| D::Error::custom(format!("invalid value: {}, {}", value, error))),
+        value => T::deserialize(value).map_err(D::Error::custom),
+    }
+}
+
+// option_from_str_or is from_str_or of an optional key, used with #[serde(default)].
+pub(crate) fn option_from_str_or<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + FromStr,
+    T::Err: Display,
+{
+    from_str_or(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spec::scalar::*;
+    use rstest::*;
+    use serde::Deserialize;
+    use std::num::NonZeroUsize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Scalars {
+        #[serde(default, deserialize_with = "option_from_str_or")]
+        count: Option<NonZeroUsize>,
+        #[serde(default, deserialize_with = "option_from_str_or")]
+        enabled: Option<bool>,
+    }
+
+    #[rstest]
+    #[case("count: 3\nenabled: true", Ok((NonZeroUsize::new(3), Some(true))))]
+    #[case("count: '3'\nenabled: 'false'", Ok((NonZeroUsize::new(3), Some(false))))]
+    #[case("{}", Ok((None, None)))]
+    #[case("count: '0'", Err(format!("invalid value: 0, number would be zero for non-zero type")))]
+    #[case("enabled: 'yes'", Err(format!("invalid value: yes, provided string was not `true` or `false`")))]
+    #[case("count: [3]", Err(format!("invalid type: sequence, expected a nonzero usize")))]
+    #[trace]
+    fn test_from_str_or(
+        #[case] input: &str,
+        #[case] expected: Result<(Option<NonZeroUsize>, Option<bool>), String>,
+    ) {
+        assert_eq!(
+            serde_yaml::from_str::<Scalars>(input)
+                .map(|scalars| (scalars.count, scalars.enabled))
+                .map_err(|error| format!("{}", error)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+// Source is the specfile and the index in its specs, where a spec is defined.
+#[derive(Debug, PartialEq, Eq, Hash, Serialize, Clone)]
+pub struct Source {
+    pub file: String,
+    pub index: usize,
+}
+
+impl Source {
+    pub fn new(file: String, index: usize) -> Source {
+        Source { file, index }
+    }
+}
@@ -0,0 +1,376 @@
+use anyhow::{anyhow, Result};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use serde_json::json;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+// Version is a GKE version like "1.27.3-gke.100", or a prefix of it like "1.27".
+// parts are major, minor, patch and gke build number.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Version {
+    parts: Vec<u64>,
+}
+
+impl Version {
+    pub fn major(&self) -> u64 {
+        self.parts[0]
+    }
+
+    pub fn minor(&self) -> Option<u64> {
+        self.parts.get(1).copied()
+    }
+
+    // compare compares self truncated to the parts of other, so that "1.27.3-gke.100" equals
+    // "1.27" and is less than "1.28". missing parts of self are treated as 0.
+    fn compare(&self, other: &Version) -> Ordering {
+        let parts = (0..other.parts.len())
+            .map(|index| self.parts.get(index).copied().unwrap_or(0))
+            .collect::<Vec<u64>>();
+        parts.cmp(&other.parts)
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Version> {
+        let invalid = || anyhow!("invalid version: {}", value);
+        let trimmed = value.trim();
+        let trimmed = trimmed.strip_prefix('v').unwrap_or(trimmed);
+        let (numbers, build) = match trimmed.split_once('-') {
+            Some((numbers, build)) => (numbers, Some(build)),
+            None => (trimmed, None),
+        };
+
+        let mut parts = numbers
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<u64>>>()?;
+        if parts.len() > 3 {
+            return Err(invalid());
+        }
+        if let Some(build) = build {
+            let number = build
+                .strip_prefix("gke.")
+                .and_then(|number| number.parse::<u64>().ok())
+                .ok_or_else(invalid)?;
+            if parts.len() != 3 {
+                return Err(invalid());
+            }
+            parts.push(number);
+        }
+        Ok(Version { parts })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let numbers = self.parts[..self.parts.len().min(3)]
+            .iter()
+            .map(|part| format!("{}", part))
+            .collect::<Vec<String>>();
+        write!(f, "{}", numbers.join("."))?;
+        if let Some(build) = self.parts.get(3) {
+            write!(f, "-gke.{}", build)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Comparator {
+    pub op: Op,
+    pub version: Version,
+}
+
+impl Comparator {
+    pub fn matches(&self, version: &Version) -> bool {
+        let ordering = version.compare(&self.version);
+        match self.op {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Ne => ordering != Ordering::Equal,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+impl FromStr for Comparator {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Comparator> {
+        let value = value.trim();
+        // longer operators first, so that ">=" is not parsed as ">".
+        let ops = [
+            (">=", Op::Ge),
+            ("<=", Op::Le),
+            ("!=", Op::Ne),
+            (">", Op::Gt),
+            ("<", Op::Lt),
+            ("=", Op::Eq),
+        ];
+        let (op, version) = ops
+            .iter()
+            .find_map(|(prefix, op)| value.strip_prefix(prefix).map(|version| (*op, version)))
+            .unwrap_or((Op::Eq, value));
+        Ok(Comparator {
+            op,
+            version: version.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Comparator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.op, self.version)
+    }
+}
+
+// VersionConstraint is given as a string of comparators which all must match, like
+// ">=1.27, <1.30", or as a list of versions one of which must match, like ["1.27", "1.28.3"].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum VersionConstraint {
+    All(Vec<Comparator>),
+    Any(Vec<Version>),
+}
+
+impl VersionConstraint {
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            Self::All(comparators) => comparators
+                .iter()
+                .all(|comparator| comparator.matches(version)),
+            Self::Any(versions) => versions
+                .iter()
+                .any(|expected| version.compare(expected) == Ordering::Equal),
+        }
+    }
+}
+
+impl FromStr for VersionConstraint {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<VersionConstraint> {
+        let comparators = value
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<Comparator>>>()?;
+        Ok(Self::All(comparators))
+    }
+}
+
+impl fmt::Display for VersionConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::All(comparators) => {
+                let comparators = comparators
+                    .iter()
+                    .map(|comparator| format!("{}", comparator))
+                    .collect::<Vec<String>>();
+                write!(f, "{}", comparators.join(", "))
+            }
+            Self::Any(versions) => {
+                let versions = versions
+                    .iter()
+                    .map(|version| format!("{}", version))
+                    .collect::<Vec<String>>();
+                write!(f, "one of {}", versions.join(", "))
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionConstraint {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let constraint = match serde_yaml::Value::deserialize(deserializer)? {
+            serde_yaml::Value::String(value) => value.parse(),
+            serde_yaml::Value::Sequence(values) => values
+                .iter()
+                .map(|value| match value {
+                    serde_yaml::Value::String(value) => value.parse(),
+                    serde_yaml::Value::Number(number) => Err(unquoted(number)),
+                    _ => Err(anyhow!("version must be a string")),
+                })
+                .collect::<Result<Vec<Version>>>()
+                .map(Self::Any),
+            serde_yaml::Value::Number(number) => Err(unquoted(&number)),
+            _ => Err(anyhow!(
+                "version constraint must be a string or an array of string"
+            )),
+        };
+        constraint.map_err(de::Error::custom)
+    }
+}
+
+// unquoted version like 1.28 is read as a number by yaml, and 1.30 becomes 1.3, so it can not be
+// converted back into the version.
+fn unquoted(number: &serde_yaml::Number) -> anyhow::Error {
+    anyhow!(
+        "version {} is read as a number, quote the version, e.g. '1.28'",
+        number
+    )
+}
+
+impl Serialize for VersionConstraint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::All(_) => serializer.serialize_str(&format!("{}", self)),
+            Self::Any(versions) => {
+                serializer.collect_seq(versions.iter().map(|version| format!("{}", version)))
+            }
+        }
+    }
+}
+
+impl JsonSchema for VersionConstraint {
+    fn schema_name() -> String {
+        format!("VersionConstraint")
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "oneOf": [
+                { "type": "string", "description": "comparators like \">=1.27, <1.30\"" },
+                { "type": "array", "items": { "type": "string" }, "description": "versions like [\"1.27\", \"1.28.3-gke.100\"]" },
+            ]
+        }))
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spec::version::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("1.27.3-gke.100", Ok(vec![1, 27, 3, 100]))]
+    #[case("v1.27.3", Ok(vec![1, 27, 3]))]
+    #[case("1.27", Ok(vec![1, 27]))]
+    #[case("1", Ok(vec![1]))]
+    #[case("1.27-gke.100", Err(format!("invalid version: 1.27-gke.100")))]
+    #[case("1.27.3-rc.1", Err(format!("invalid version: 1.27.3-rc.1")))]
+    #[case("1.x", Err(format!("invalid version: 1.x")))]
+    #[case("1.2.3.4", Err(format!("invalid version: 1.2.3.4")))]
+    #[case("", Err(format!("invalid version: ")))]
+    #[trace]
+    fn test_version_from_str(#[case] input: &str, #[case] expected: Result<Vec<u64>, String>) {
+        let actual = input
+            .parse::<Version>()
+            .map(|version| version.parts)
+            .map_err(|error| format!("{}", error));
+        assert_eq!(actual, expected);
+    }
+
+    #[rstest]
+    #[case(">=1.27, <1.30", "1.27.3-gke.100", true)]
+    #[case(">=1.27, <1.30", "1.29.9-gke.1", true)]
+    #[case(">=1.27, <1.30", "1.30.0-gke.1", false)]
+    #[case(">=1.27, <1.30", "1.26.15-gke.1", false)]
+    #[case(">1.27", "1.27.9-gke.1", false)]
+    #[case(">1.27", "1.28.0-gke.1", true)]
+    #[case("<=1.27", "1.27.9-gke.1", true)]
+    #[case("=1.27.3", "1.27.3-gke.100", true)]
+    #[case("1.27.3-gke.100", "1.27.3-gke.200", false)]
+    #[case("!=1.27.3", "1.27.3-gke.100", false)]
+    #[case(">= 1.27.3-gke.100", "1.27.3-gke.200", true)]
+    #[trace]
+    fn test_constraint_matches(
+        #[case] constraint: &str,
+        #[case] version: &str,
+        #[case] expected: bool,
+    ) {
+        let constraint = constraint.parse::<VersionConstraint>().unwrap();
+        let version = version.parse::<Version>().unwrap();
+        assert_eq!(constraint.matches(&version), expected);
+    }
+
+    #[rstest]
+    #[case("version: '>=1.27, <1.30'\n", "1.28.1-gke.1", true)]
+    #[case("version:\n- '1.27'\n- 1.28.3-gke.100\n", "1.28.3-gke.100", true)]
+    #[case("version:\n- '1.27'\n- 1.28.3-gke.100\n", "1.28.3-gke.200", false)]
+    #[trace]
+    fn test_constraint_deserialize(
+        #[case] input: &str,
+        #[case] version: &str,
+        #[case] expected: bool,
+    ) {
+        #[derive(serde::Deserialize, serde::Serialize)]
+        struct Config {
+            version: VersionConstraint,
+        }
+
+        let config = serde_yaml::from_str::<Config>(input).unwrap();
+        let version = version.parse::<Version>().unwrap();
+        assert_eq!(config.version.matches(&version), expected);
+        assert_eq!(serde_yaml::to_string(&config).unwrap(), input);
+    }
+
+    #[rstest]
+    #[case("version: '>=1.x'\n", "invalid version: 1.x")]
+    #[case(
+        "version: {min: 1}\n",
+        "version constraint must be a string or an array of string"
+    )]
+    #[case(
+        "version: 1.28\n",
+        "version 1.28 is read as a number, quote the version, e.g. '1.28'"
+    )]
+    #[case(
+        "version: 1.30\n",
+        "version 1.3 is read as a number, quote the version, e.g. '1.28'"
+    )]
+    #[case(
+        "version:\n- '1.27'\n- 1.28\n",
+        "version 1.28 is read as a number, quote the version, e.g. '1.28'"
+    )]
+    #[case("version:\n- [1.27]\n", "version must be a string")]
+    #[trace]
+    fn test_constraint_deserialize_error(#[case] input: &str, #[case] expected: &str) {
+        #[derive(Debug, serde::Deserialize)]
+        struct Config {
+            #[allow(dead_code)]
+            version: VersionConstraint,
+        }
+
+        let error = serde_yaml::from_str::<Config>(input).unwrap_err();
+        assert!(format!("{}", error).starts_with(expected), "{}", error);
+    }
+}
@@ -0,0 +1,548 @@
+pub mod generator;
+pub mod interpolation;
+pub mod loader;
+pub mod location;
+pub mod schema;
+pub mod settings;
+pub mod validation;
+
+use anyhow::{anyhow, Context, Result};
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+use crate::operator::registry::registry;
+use crate::spec::{Spec, COMMON_KEYS};
+use crate::specfile::interpolation::interpolate;
+use crate::specfile::settings::Settings;
+
+pub const SPECFILE_VERSION: u64 = 1;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Specfile {
+    pub settings: Settings,
+    pub specs: Vec<Spec>,
+    // paths or glob patterns of specfiles to include, relative to the specfile.
+    pub includes: Vec<String>,
+}
+
+// parse specfile, which is a list of specs or a document mapping with "specs" or "include" and
+// optional "version", "vars", "defaults" and "settings".
+// variables in string values are interpolated from environment variables, and then from vars.
+pub fn parse(input: &str) -> Result<Specfile> {
+    parse_with_env(input, &|name| std::env::var(name).ok())
+}
+
+pub fn parse_with_env<F>(input: &str, env: &F) -> Result<Specfile>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let document = parse_document(input, env)?;
+    let specs = document
+        .specs
+        .into_iter()
+        .enumerate()
+        .map(|(index, spec)| {
+            serde_yaml::from_value::<Spec>(spec)
+                .with_context(|| format!("invalid spec at index {}", index))
+        })
+        .collect::<Result<Vec<Spec>>>()?;
+
+    Ok(Specfile {
+        settings: document.settings,
+        specs,
+        includes: document.includes,
+    })
+}
+
+// Document is a specfile whose specs are interpolated and merged with defaults, but not
+// deserialized yet.
+pub(crate) struct Document {
+    pub settings: Settings,
+    pub includes: Vec<String>,
+    pub specs: Vec<Value>,
+    // bare document is a list of specs, not a mapping.
+    pub bare: bool,
+}
+
+pub(crate) fn parse_document<F>(input: &str, env: &F) -> Result<Document>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let value = serde_yaml::from_str::<Value>(input)?;
+    let bare = !value.is_mapping();
+    let mut document = match value {
+        Value::Mapping(document) => document,
+        specs => {
+            let mut document = Mapping::new();
+            document.insert(Value::from("specs"), specs);
+            document
+        }
+    };
+
+    if let Some(version) = document.remove("version") {
+        if version.as_u64() != Some(SPECFILE_VERSION) {
+            return Err(anyhow!(
+                "unsupported version: {}, supported version is {}",
+                describe(&version),
+                SPECFILE_VERSION
+            ));
+        }
+    }
+    let vars = match document.remove("vars") {
+        Some(vars) => parse_vars(vars, env)?,
+        None => HashMap::new(),
+    };
+    let defaults = document.remove("defaults");
+    let settings = document.remove("settings");
+    let includes = document.remove("include");
+    let specs = match (document.remove("specs"), &includes) {
+        (Some(specs), _) => specs,
+        (None, Some(_)) => Value::Sequence(vec![]),
+        (None, None) => return Err(anyhow!("specs is not found")),
+    };
+    if let Some(key) = document.keys().next() {
+        return Err(anyhow!("unknown key: {}", describe(key)));
+    }
+
+    let lookup = |name: &str| env(name).or_else(|| vars.get(name).cloned());
+    let settings = match settings {
+        Some(settings) => serde_yaml::from_value::<Settings>(interpolate_value(settings, &lookup)?)
+            .context("invalid settings")?,
+        None => Settings::default(),
+    };
+    settings.validate().context("invalid settings")?;
+    let includes = match includes.map(|includes| interpolate_value(includes, &lookup)) {
+        Some(Ok(Value::String(include))) => vec![include],
+        Some(includes) => serde_yaml::from_value::<Vec<String>>(includes?)
+            .context("include must be a path or a list of paths")?,
+        None => vec![],
+    };
+    let mut specs = serde_yaml::from_value::<Vec<Value>>(interpolate_value(specs, &lookup)?)
+        .context("specs must be a list of specs")?;
+    if let Some(defaults) = defaults {
+        let defaults = serde_yaml::from_value::<Mapping>(interpolate_value(defaults, &lookup)?)
+            .context("defaults must be a mapping")?;
+        merge_defaults(&mut specs, &defaults);
+    }
+
+    Ok(Document {
+        settings,
+        includes,
+        specs,
+        bare,
+    })
+}
+
+// keys of defaults are added to each spec, unless the spec has the key or its operator does not
+// declare the key, so that defaults can have keys of several operators.
+fn merge_defaults(specs: &mut [Value], defaults: &Mapping) {
+    let registry = registry();
+    for spec in specs {
+        let fields = match spec
+            .get("operator")
+            .and_then(Value::as_str)
+            .and_then(|operator| registry.fields(operator))
+        {
+            Some(fields) => fields,
+            None => continue,
+        };
+        if let Value::Mapping(spec) = spec {
+            for (key, value) in defaults {
+                let declared = key.as_str().is_some_and(|key| {
+                    COMMON_KEYS.contains(&key) || fields.iter().any(|field| field == key)
+                });
+                if declared && !spec.contains_key(key) {
+                    spec.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+// vars can refer environment variables, but can not refer other vars.
+fn parse_vars<F>(vars: Value, env: &F) -> Result<HashMap<String, String>>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let vars = serde_yaml::from_value::<Mapping>(vars).context("vars must be a mapping")?;
+    vars.into_iter()
+        .map(|(name, value)| {
+            let name = match name {
+                Value::String(name) => name,
+                name => return Err(anyhow!("invalid variable name: {}", describe(&name))),
+            };
+            let value = match value {
+                Value::String(value) => interpolate(&value, env)?,
+                Value::Number(value) => format!("{}", value),
+                Value::Bool(value) => format!("{}", value),
+                _ => return Err(anyhow!("value of variable {} must be a scalar", name)),
+            };
+            Ok((name, value))
+        })
+        .collect()
+}
+
+fn interpolate_value<F>(value: Value, lookup: &F) -> Result<Value>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match value {
+        Value::String(value) => Ok(Value::String(interpolate(&value, lookup)?)),
+        Value::Sequence(values) => Ok(Value::Sequence(
+            values
+                .into_iter()
+                .map(|value| interpolate_value(value, lookup))
+                .collect::<Result<_>>()?,
+        )),
+        Value::Mapping(mapping) => Ok(Value::Mapping(
+            mapping
+                .into_iter()
+                .map(|(key, value)| Ok((key, interpolate_value(value, lookup)?)))
+                .collect::<Result<_>>()?,
+        )),
+        value => Ok(value),
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match serde_yaml::to_string(value) {
+        Ok(value) => format!("{}", value.trim_end()),
+        Err(_) => format!("{:?}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operator::gke_cluster_status_operator::*;
+    use crate::operator::gke_node_pool_autoscaling_operator::*;
+    use crate::operator::gke_node_pool_size_operator::*;
+    use crate::operator::gke_node_pool_status_operator::*;
+    use crate::report::format::*;
+    use crate::spec::cluster_status::*;
+    use crate::spec::node_pool_status::*;
+    use crate::spec::range::*;
+    use crate::spec::*;
+    use crate::specfile::*;
+    use rstest::*;
+    use std::num::NonZeroUsize;
+    use std::time::Duration;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "ENV" => Some(format!("prod")),
+            "PROJECT" => Some(format!("env-project")),
+            _ => None,
+        }
+    }
+
+    fn cluster_spec(project: &str, location: &str, cluster: &str) -> Spec {
+        Spec::new(GKEClusterStatusOperator {
+            project: format!("{}", project),
+            location: format!("{}", location),
+            cluster: format!("{}", cluster),
+            status: vec![ClusterStatus::Running],
+        })
+    }
+
+    #[rstest]
+    #[case(
+        format!(
+r#"- operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+"#
+        ),
+        vec![cluster_spec("project", "location", "cluster")]
+    )]
+    #[case(
+        format!(
+r#"- operator: GKEClusterStatus
+  project: ${{PROJECT}}
+  location: ${{LOCATION:-asia-northeast1}}
+  cluster: cluster-${{ENV}}
+  status:
+  - Running
+"#
+        ),
+        vec![cluster_spec("env-project", "asia-northeast1", "cluster-prod")]
+    )]
+    #[case(
+        format!(
+r#"vars:
+  PROJECT: vars-project
+  LOCATION: asia-northeast1
+  CLUSTER: cluster-${{ENV}}
+specs:
+- operator: GKEClusterStatus
+  project: ${{PROJECT}}
+  location: ${{LOCATION}}
+  cluster: ${{CLUSTER}}
+  status:
+  - Running
+"#
+        ),
+        vec![cluster_spec("env-project", "asia-northeast1", "cluster-prod")]
+    )]
+    #[case(
+        format!(
+r#"specs:
+- operator: GKEClusterStatus
+  project: $${{PROJECT}}
+  location: location
+  cluster: cluster
+  status:
+  - Running
+"#
+        ),
+        vec![cluster_spec("${PROJECT}", "location", "cluster")]
+    )]
+    #[trace]
+    fn test_parse(#[case] input: String, #[case] expected: Vec<Spec>) {
+        assert_eq!(parse_with_env(&input, &env).unwrap().specs, expected);
+    }
+
+    #[rstest]
+    fn test_parse_document() {
+        let input = format!(
+            r#"version: 1
+vars:
+  CLUSTER: cluster-${{ENV}}
+defaults:
+  project: default-project
+  location: asia-northeast1
+  cluster: ${{CLUSTER}}
+  node_pool: default-node-pool
+  timeout: 1m
+settings:
+  concurrency: 2
+  timeout: 30s
+  format: json
+specs:
+- operator: GKEClusterStatus
+  status:
+  - Running
+- operator: GKENodePoolStatus
+  project: other-project
+  node_pool: node-pool
+  status:
+  - Running
+"#
+        );
+
+        assert_eq!(
+            parse_with_env(&input, &env).unwrap(),
+            Specfile {
+                settings: Settings {
+                    concurrency: NonZeroUsize::new(2),
+                    timeout: Some(Duration::from_secs(30)),
+                    format: Some(Format::Json),
+                    ..Default::default()
+                },
+                specs: vec![
+                    Spec {
+                        timeout: Some(Duration::from_secs(60)),
+                        ..cluster_spec("default-project", "asia-northeast1", "cluster-prod")
+                    },
+                    Spec {
+                        timeout: Some(Duration::from_secs(60)),
+                        ..Spec::new(GKENodePoolStatusOperator {
+                            project: format!("other-project"),
+                            location: format!("asia-northeast1"),
+                            cluster: format!("cluster-prod"),
+                            node_pool: format!("node-pool"),
+                            status: vec![NodePoolStatus::Running],
+                        })
+                    },
+                ],
+                includes: vec![],
+            }
+        );
+    }
+
+    // interpolated values are strings, even if the variable looks like a number or a boolean.
+    #[rstest]
+    #[case("${N}", Value::from("3"))]
+    #[case("${ENABLED:-true}", Value::from("true"))]
+    #[case("${VERSION}", Value::from("1.30"))]
+    #[case("${N}-${N}", Value::from("3-3"))]
+    #[case("n${N}", Value::from("n3"))]
+    #[case("${NAME}", Value::from("node-pool"))]
+    #[trace]
+    fn test_interpolate_value(#[case] value: &str, #[case] expected: Value) {
+        let lookup = |name: &str| match name {
+            "N" => Some(format!("3")),
+            "VERSION" => Some(format!("1.30")),
+            "NAME" => Some(format!("node-pool")),
+            _ => None,
+        };
+        assert_eq!(
+            interpolate_value(Value::from(value), &lookup).unwrap(),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_parse_digits_variable() {
+        let input = format!(
+            r#"vars:
+  PROJECT_NUMBER: "123456789"
+  CLUSTER: 42
+specs:
+- id: ${{CLUSTER}}
+  operator: GKEClusterStatus
+  project: ${{PROJECT_NUMBER}}
+  location: location
+  cluster: ${{CLUSTER}}
+  status:
+  - Running
+  tags:
+  - ${{CLUSTER}}
+"#
+        );
+
+        let specfile = parse_with_env(&input, &env).unwrap();
+        assert_eq!(
+            specfile.specs,
+            vec![Spec {
+                id: Some(format!("42")),
+                tags: vec![format!("42")],
+                ..Spec::new(GKEClusterStatusOperator {
+                    project: format!("123456789"),
+                    location: format!("location"),
+                    cluster: format!("42"),
+                    status: vec![ClusterStatus::Running],
+                })
+            }]
+        );
+    }
+
+    #[rstest]
+    fn test_parse_numeric_variable() {
+        let input = format!(
+            r#"vars:
+  N: 3
+  C: "2"
+  ENABLED: true
+settings:
+  concurrency: ${{C}}
+specs:
+- operator: GKENodePoolSize
+  project: project
+  location: location
+  cluster: cluster
+  node_count: ${{N}}
+- operator: GKENodePoolAutoscaling
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  enabled: ${{ENABLED}}
+  max_node_count:
+    min: ${{N}}
+"#
+        );
+
+        let specfile = parse_with_env(&input, &env).unwrap();
+        assert_eq!(specfile.settings.concurrency, NonZeroUsize::new(2));
+        assert_eq!(
+            specfile.specs,
+            vec![
+                Spec::new(GKENodePoolSizeOperator::new(
+                    format!("project"),
+                    format!("location"),
+                    format!("cluster"),
+                    None,
+                    Range {
+                        min: Some(3),
+                        max: Some(3)
+                    },
+                )),
+                Spec::new(GKENodePoolAutoscalingOperator::new(
+                    format!("project"),
+                    format!("location"),
+                    format!("cluster"),
+                    format!("node_pool"),
+                    true,
+                    None,
+                    Some(Range {
+                        min: Some(3),
+                        max: None
+                    }),
+                ))
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(format!("include: team.yml\n"), vec![format!("team.yml")])]
+    #[case(
+        format!("include:\n- teams/*.yml\n- ${{ENV}}.yml\nspecs: []\n"),
+        vec![format!("teams/*.yml"), format!("prod.yml")]
+    )]
+    #[case(format!("specs: []\n"), vec![])]
+    #[trace]
+    fn test_parse_include(#[case] input: String, #[case] expected: Vec<String>) {
+        assert_eq!(parse_with_env(&input, &env).unwrap().includes, expected);
+    }
+
+    #[rstest]
+    #[case(
+        format!(
+r#"- operator: GKEClusterStatus
+  project: ${{UNDEFINED}}
+  location: location
+  cluster: cluster
+  status:
+  - Running
+"#
+        ),
+        format!("undefined variable: UNDEFINED")
+    )]
+    #[case(
+        format!(
+r#"vars:
+  PROJECT: ${{UNDEFINED}}
+specs: []
+"#
+        ),
+        format!("undefined variable: UNDEFINED")
+    )]
+    #[case(
+        format!(
+r#"vars:
+  PROJECT: [project]
+specs: []
+"#
+        ),
+        format!("value of variable PROJECT must be a scalar")
+    )]
+    #[case(format!("vars: {{}}\n"), format!("specs is not found"))]
+    #[case(format!("specs: []\nunknown: value\n"), format!("unknown key: unknown"))]
+    #[case(
+        format!("version: 2\nspecs: []\n"),
+        format!("unsupported version: 2, supported version is 1")
+    )]
+    #[case(format!("defaults: []\nspecs: []\n"), format!("defaults must be a mapping"))]
+    #[case(
+        format!("include: {{}}\n"),
+        format!("include must be a path or a list of paths")
+    )]
+    #[case(
+        format!("settings:\n  unknown: value\nspecs: []\n"),
+        format!("invalid settings")
+    )]
+    #[case(
+        format!("settings:\n  plaintext: true\n  ca_certificate: ca.pem\nspecs: []\n"),
+        format!("invalid settings")
+    )]
+    #[trace]
+    fn test_parse_error(#[case] input: String, #[case] expected: String) {
+        match parse_with_env(&input, &env) {
+            Ok(_) => panic!("parse should fail"),
+            Err(error) => assert_eq!(format!("{}", error), expected),
+        }
+    }
+}
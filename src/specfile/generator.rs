@@ -0,0 +1,207 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::client::gke_client::GKEClientTrait;
+use crate::operator::gke_cluster_status_operator::GKEClusterStatusOperator;
+use crate::operator::gke_node_pool_status_operator::GKENodePoolStatusOperator;
+use crate::spec::cluster_status::ClusterStatus;
+use crate::spec::node_pool_status::NodePoolStatus;
+use crate::spec::Spec;
+use crate::specfile::SPECFILE_VERSION;
+
+#[derive(Serialize)]
+struct Generated<'a> {
+    version: u64,
+    specs: &'a [Spec],
+}
+
+// generate specs of all clusters and node pools in project, which expect them to be healthy.
+// location "-" means all locations.
+pub async fn generate(
+    client: &Arc<dyn GKEClientTrait>,
+    project: &str,
+    location: &str,
+) -> Result<Vec<Spec>> {
+    let mut clusters = client.list_clusters(project, location).await?.value;
+    clusters.sort_by(|a, b| (&a.location, &a.name).cmp(&(&b.location, &b.name)));
+
+    let mut specs = vec![];
+    for cluster in clusters {
+        if cluster.status != ClusterStatus::Running {
+            log::warn!(
+                "{}/{} is {}, but spec expects it to be Running",
+                cluster.location,
+                cluster.name,
+                cluster.status
+            );
+        }
+        specs.push(Spec {
+            id: Some(format!("{}/{}", cluster.location, cluster.name)),
+            ..Spec::new(GKEClusterStatusOperator {
+                project: format!("{}", project),
+                location: cluster.location.clone(),
+                cluster: cluster.name.clone(),
+                status: vec![ClusterStatus::Running],
+            })
+        });
+
+        let mut node_pools = client
+            .list_node_pools(project, &cluster.location, &cluster.name)
+            .await?
+            .value;
+        node_pools.sort_by(|a, b| a.name.cmp(&b.name));
+        for node_pool in node_pools {
+            if node_pool.status != NodePoolStatus::Running {
+                log::warn!(
+                    "{}/{}/{} is {}, but spec expects it to be Running",
+                    cluster.location,
+                    cluster.name,
+                    node_pool.name,
+                    node_pool.status
+                );
+            }
+            specs.push(Spec {
+                id: Some(format!(
+                    "{}/{}/{}",
+                    cluster.location, cluster.name, node_pool.name
+                )),
+                ..Spec::new(GKENodePoolStatusOperator {
+                    project: format!("{}", project),
+                    location: cluster.location.clone(),
+                    cluster: cluster.name.clone(),
+                    node_pool: node_pool.name,
+                    status: vec![NodePoolStatus::Running],
+                })
+            });
+        }
+    }
+    Ok(specs)
+}
+
+pub fn render(specs: &[Spec]) -> Result<String> {
+    Ok(serde_yaml::to_string(&Generated {
+        version: SPECFILE_VERSION,
+        specs,
+    })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::gke_client::*;
+    use crate::client::retry::*;
+    use crate::specfile::generator::*;
+    use crate::specfile::parse;
+    use mockall::predicate::eq;
+    use rstest::*;
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate() {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_list_clusters()
+            .with(eq("project"), eq("-"))
+            .times(1)
+            .returning(|_, _| {
+                Ok(Fetched::new(
+                    vec![
+                        ClusterSummary {
+                            name: format!("cluster-b"),
+                            location: format!("asia-northeast1"),
+                            status: ClusterStatus::Reconciling,
+                        },
+                        ClusterSummary {
+                            name: format!("cluster-a"),
+                            location: format!("asia-northeast1-a"),
+                            status: ClusterStatus::Running,
+                        },
+                    ],
+                    0,
+                ))
+            });
+        client
+            .expect_list_node_pools()
+            .with(eq("project"), eq("asia-northeast1"), eq("cluster-b"))
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(Fetched::new(
+                    vec![
+                        NodePoolSummary {
+                            name: format!("pool-2"),
+                            status: NodePoolStatus::Running,
+                        },
+                        NodePoolSummary {
+                            name: format!("pool-1"),
+                            status: NodePoolStatus::RunningWithError,
+                        },
+                    ],
+                    0,
+                ))
+            });
+        client
+            .expect_list_node_pools()
+            .with(eq("project"), eq("asia-northeast1-a"), eq("cluster-a"))
+            .times(1)
+            .returning(|_, _, _| Ok(Fetched::new(vec![], 0)));
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        let specs = generate(&client, "project", "-").await.unwrap();
+        let rendered = render(&specs).unwrap();
+
+        assert_eq!(
+            rendered,
+            format!(
+                r#"version: 1
+specs:
+- id: asia-northeast1/cluster-b
+  operator: GKEClusterStatus
+  project: project
+  location: asia-northeast1
+  cluster: cluster-b
+  status:
+  - Running
+- id: asia-northeast1/cluster-b/pool-1
+  operator: GKENodePoolStatus
+  project: project
+  location: asia-northeast1
+  cluster: cluster-b
+  node_pool: pool-1
+  status:
+  - Running
+- id: asia-northeast1/cluster-b/pool-2
+  operator: GKENodePoolStatus
+  project: project
+  location: asia-northeast1
+  cluster: cluster-b
+  node_pool: pool-2
+  status:
+  - Running
+- id: asia-northeast1-a/cluster-a
+  operator: GKEClusterStatus
+  project: project
+  location: asia-northeast1-a
+  cluster: cluster-a
+  status:
+  - Running
+"#
+            )
+        );
+        assert_eq!(parse(&rendered).unwrap().specs, specs);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_generate_error() {
+        let mut client = MockGKEClientTrait::new();
+        client
+            .expect_list_clusters()
+            .returning(|_, _| Err(anyhow::anyhow!("permission denied")));
+        let client: Arc<dyn GKEClientTrait> = Arc::new(client);
+
+        match generate(&client, "project", "-").await {
+            Ok(_) => panic!("generate should fail"),
+            Err(error) => assert_eq!(format!("{}", error), "permission denied"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+
+// interpolate replaces "${NAME}" and "${NAME:-default}" in value with variables found by lookup.
+// "$$" is replaced with "$", so "$${NAME}" is not interpolated.
+pub fn interpolate<F>(value: &str, lookup: &F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut interpolated = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('$') {
+        interpolated.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("$$") {
+            interpolated.push('$');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("${") {
+            let end = after
+                .find('}')
+                .ok_or_else(|| anyhow!("unclosed variable in \"{}\"", value))?;
+            interpolated.push_str(&resolve(&after[..end], lookup)?);
+            rest = &after[end + 1..];
+        } else {
+            interpolated.push('$');
+            rest = &rest[1..];
+        }
+    }
+    interpolated.push_str(rest);
+    Ok(interpolated)
+}
+
+fn resolve<F>(expression: &str, lookup: &F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let (name, default) = match expression.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (expression, None),
+    };
+    if !is_valid_name(name) {
+        return Err(anyhow!("invalid variable name: \"{}\"", name));
+    }
+    match (lookup(name), default) {
+        (Some(value), _) => Ok(value),
+        (None, Some(default)) => Ok(format!("{}", default)),
+        (None, None) => Err(anyhow!("undefined variable: {}", name)),
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specfile::interpolation::*;
+    use rstest::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "PROJECT" => Some(format!("project-001")),
+            "LOCATION" => Some(format!("asia-northeast1")),
+            "EMPTY" => Some(format!("")),
+            _ => None,
+        }
+    }
+
+    #[rstest]
+    #[case("cluster-001", Ok(format!("cluster-001")))]
+    #[case("${PROJECT}", Ok(format!("project-001")))]
+    #[case(
+        "${PROJECT}/${LOCATION}",
+        Ok(format!("project-001/asia-northeast1"))
+    )]
+    #[case("cluster-${EMPTY}001", Ok(format!("cluster-001")))]
+    #[case("${CLUSTER:-cluster-001}", Ok(format!("cluster-001")))]
+    #[case("${PROJECT:-default}", Ok(format!("project-001")))]
+    #[case("${CLUSTER:-}", Ok(format!("")))]
+    #[case("$${PROJECT}", Ok(format!("${{PROJECT}}")))]
+    #[case("cost $5", Ok(format!("cost $5")))]
+    #[case("${CLUSTER}", Err(format!("undefined variable: CLUSTER")))]
+    #[case("${PROJECT", Err(format!("unclosed variable in \"${{PROJECT\"")))]
+    #[case("${1PROJECT}", Err(format!("invalid variable name: \"1PROJECT\"")))]
+    #[case("${}", Err(format!("invalid variable name: \"\"")))]
+    #[trace]
+    fn test_interpolate(#[case] value: &str, #[case] expected: Result<String, String>) {
+        assert_eq!(
+            interpolate(value, &lookup).map_err(|e| format!("{}", e)),
+            expected
+        );
+    }
+}
@@ -0,0 +1,340 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::spec::source::Source;
+use crate::specfile::{parse, Specfile};
+
+// load specfiles matched by patterns, and specfiles included by them.
+// specs are listed in load order, and settings of earlier specfile are preferred.
+pub fn load(patterns: &[String]) -> Result<Specfile> {
+    let mut specfile = Specfile::default();
+    let mut top_level = 0;
+    // settings of connection are checked after all specfiles given on command line are counted.
+    let mut connections = vec![];
+    walk(patterns, |path, input, included| {
+        let mut parsed = parse(input)
+            .with_context(|| format!("failed to parse specfile: {}", path.display()))?;
+        if !included {
+            top_level += 1;
+        }
+        connections.push((path.to_path_buf(), included, parsed.settings.clone()));
+        // ca certificate is relative to the specfile, like includes.
+        if let Some(ca_certificate) = &mut parsed.settings.ca_certificate {
+            if let Some(base) = path
+                .parent()
+                .filter(|_| Path::new(ca_certificate).is_relative())
+            {
+                *ca_certificate = format!("{}", base.join(&ca_certificate).display());
+            }
+        }
+
+        let file = format!("{}", path.display());
+        specfile.settings = specfile.settings.clone().or(parsed.settings);
+        specfile.specs.extend(
+            parsed
+                .specs
+                .into_iter()
+                .enumerate()
+                .map(|(index, mut spec)| {
+                    spec.source = Some(Source::new(file.clone(), index));
+                    spec
+                }),
+        );
+        Ok(parsed.includes)
+    })?;
+    for (path, included, settings) in connections {
+        settings
+            .validate_connection(included, top_level)
+            .with_context(|| format!("failed to parse specfile: {}", path.display()))?;
+    }
+    Ok(specfile)
+}
+
+// walk visits specfiles matched by patterns, and specfiles included by them.
+// visit receives path and content of a specfile, and whether it is included by another
+// specfile, and returns its includes.
+pub(crate) fn walk<F>(patterns: &[String], mut visit: F) -> Result<()>
+where
+    F: FnMut(&Path, &str, bool) -> Result<Vec<String>>,
+{
+    let mut walker = Walker::default();
+    for pattern in patterns {
+        for path in expand(pattern, None)? {
+            walker.walk_file(&path, &mut visit)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Walker {
+    // canonical paths of specfiles being loaded, to detect include cycle.
+    stack: Vec<PathBuf>,
+    // canonical paths of loaded specfiles, a specfile is loaded only once.
+    loaded: HashSet<PathBuf>,
+}
+
+impl Walker {
+    fn walk_file<F>(&mut self, path: &Path, visit: &mut F) -> Result<()>
+    where
+        F: FnMut(&Path, &str, bool) -> Result<Vec<String>>,
+    {
+        let canonical = fs::canonicalize(path)
+            .with_context(|| format!("failed to open specfile: {}", path.display()))?;
+        if let Some(position) = self.stack.iter().position(|p| *p == canonical) {
+            let cycle = self.stack[position..]
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| format!("{}", p.display()))
+                .collect::<Vec<_>>();
+            return Err(anyhow!("include cycle: {}", cycle.join(" -> ")));
+        }
+        if !self.loaded.insert(canonical.clone()) {
+            log::debug!("skip specfile loaded already: {}", path.display());
+            return Ok(());
+        }
+
+        log::debug!("load specfile: {}", path.display());
+        let input = fs::read_to_string(path)
+            .with_context(|| format!("failed to open specfile: {}", path.display()))?;
+        let includes = visit(path, &input, !self.stack.is_empty())?;
+
+        self.stack.push(canonical);
+        for include in &includes {
+            for included in expand(include, path.parent())? {
+                self.walk_file(&included, visit)
+                    .with_context(|| format!("failed to include specfile: {}", path.display()))?;
+            }
+        }
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+// expand glob pattern into paths. relative pattern is resolved from base directory if given.
+fn expand(pattern: &str, base: Option<&Path>) -> Result<Vec<PathBuf>> {
+    let path = match base {
+        Some(base) if Path::new(pattern).is_relative() => base.join(pattern),
+        _ => PathBuf::from(pattern),
+    };
+    if !pattern.contains(['*', '?', '[']) {
+        return Ok(vec![path]);
+    }
+
+    let glob = format!("{}", path.display());
+    let paths = glob::glob(&glob)
+        .with_context(|| format!("invalid pattern: {}", glob))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if paths.is_empty() {
+        return Err(anyhow!("no specfile matches: {}", glob));
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specfile::loader::*;
+    use rstest::*;
+    use tempfile::TempDir;
+
+    const CLUSTER_SPEC: &str = r#"
+- operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+"#;
+
+    // write files into new temporary directory, which is removed when it is dropped.
+    fn write_files(files: Vec<(&str, &str)>) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, content) in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn sources(specfile: &Specfile, dir: &Path) -> Vec<(String, usize)> {
+        specfile
+            .specs
+            .iter()
+            .map(|spec| {
+                let source = spec.source.clone().unwrap();
+                let file = Path::new(&source.file).strip_prefix(dir).unwrap();
+                (format!("{}", file.display()), source.index)
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_load_patterns() {
+        let two_specs = format!("{}{}", CLUSTER_SPEC, &CLUSTER_SPEC[1..]);
+        let dir = write_files(vec![
+            ("team-b/spec.yml", CLUSTER_SPEC),
+            ("team-a/spec.yml", &two_specs),
+            ("root.yml", CLUSTER_SPEC),
+        ]);
+        let patterns = vec![
+            format!("{}", dir.path().join("root.yml").display()),
+            format!("{}", dir.path().join("team-*/spec.yml").display()),
+        ];
+
+        let specfile = load(&patterns).unwrap();
+
+        assert_eq!(
+            sources(&specfile, dir.path()),
+            vec![
+                (format!("root.yml"), 0),
+                (format!("team-a/spec.yml"), 0),
+                (format!("team-a/spec.yml"), 1),
+                (format!("team-b/spec.yml"), 0),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_load_include() {
+        let dir = write_files(vec![
+            (
+                "root.yml",
+                "include:\n- teams/*.yml\n- common.yml\nsettings:\n  concurrency: 2\n",
+            ),
+            ("teams/a.yml", CLUSTER_SPEC),
+            (
+                "teams/b.yml",
+                &format!(
+                    "include: ../common.yml\nsettings:\n  concurrency: 8\n  timeout: 1m\nspecs:{}",
+                    CLUSTER_SPEC
+                ),
+            ),
+            ("common.yml", CLUSTER_SPEC),
+        ]);
+
+        let specfile = load(&[format!("{}", dir.path().join("root.yml").display())]).unwrap();
+
+        // common.yml is included twice, but loaded once.
+        assert_eq!(
+            sources(&specfile, dir.path()),
+            vec![
+                (format!("teams/a.yml"), 0),
+                (format!("teams/b.yml"), 0),
+                (format!("teams/../common.yml"), 0),
+            ]
+        );
+        assert_eq!(specfile.settings.concurrency.unwrap().get(), 2);
+        assert_eq!(
+            specfile.settings.timeout,
+            Some(std::time::Duration::from_secs(60))
+        );
+    }
+
+    #[rstest]
+    fn test_load_ca_certificate() {
+        let dir = write_files(vec![(
+            "teams/root.yml",
+            &format!(
+                "settings:\n  ca_certificate: certs/ca.pem\nspecs:{}",
+                CLUSTER_SPEC
+            ),
+        )]);
+
+        let specfile = load(&[format!("{}", dir.path().join("teams/root.yml").display())]).unwrap();
+
+        // ca certificate is relative to the specfile which has it.
+        assert_eq!(
+            specfile.settings.ca_certificate,
+            Some(format!(
+                "{}",
+                dir.path().join("teams/certs/ca.pem").display()
+            ))
+        );
+    }
+
+    #[rstest]
+    #[case("root.yml", "endpoint: https://attacker.example.com", false)]
+    #[case("root.yml", "plaintext: true", false)]
+    #[case("root.yml", "ca_certificate: ca.pem", false)]
+    #[case(
+        "root.yml",
+        "compute_endpoint: https://attacker.example.com/compute/v1",
+        false
+    )]
+    #[case("teams/*.yml", "endpoint: https://attacker.example.com", false)]
+    #[case(
+        "teams/*.yml",
+        "compute_endpoint: https://attacker.example.com/compute/v1",
+        false
+    )]
+    #[case("teams/b.yml", "endpoint: https://endpoint", true)]
+    #[trace]
+    fn test_load_endpoint(#[case] pattern: &str, #[case] settings: &str, #[case] expected: bool) {
+        // teams/b.yml is included by root.yml, or matched by a glob together with teams/a.yml.
+        let dir = write_files(vec![
+            ("root.yml", "include: teams/b.yml\n"),
+            ("teams/a.yml", CLUSTER_SPEC),
+            (
+                "teams/b.yml",
+                &format!("settings:\n  {}\nspecs:{}", settings, CLUSTER_SPEC),
+            ),
+        ]);
+
+        match load(&[format!("{}", dir.path().join(pattern).display())]) {
+            Ok(specfile) => {
+                assert!(expected, "endpoint settings should be rejected");
+                assert_eq!(
+                    specfile.settings.endpoint,
+                    Some(format!("https://endpoint"))
+                );
+            }
+            Err(error) => {
+                assert!(!expected, "{:#}", error);
+                assert_eq!(
+                    format!("{}", error.root_cause()),
+                    "endpoint, plaintext, ca_certificate and compute_endpoint of settings can be given only when a single specfile is given on command line"
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_load_include_cycle() {
+        let tmp = write_files(vec![
+            ("a.yml", "include: b.yml\n"),
+            ("b.yml", "include: c.yml\n"),
+            ("c.yml", "include: b.yml\n"),
+        ]);
+        let dir = fs::canonicalize(tmp.path()).unwrap();
+
+        match load(&[format!("{}", dir.join("a.yml").display())]) {
+            Ok(_) => panic!("include cycle should be detected"),
+            Err(error) => assert_eq!(
+                format!("{}", error.root_cause()),
+                format!(
+                    "include cycle: {} -> {} -> {}",
+                    dir.join("b.yml").display(),
+                    dir.join("c.yml").display(),
+                    dir.join("b.yml").display()
+                )
+            ),
+        }
+    }
+
+    #[rstest]
+    #[case(format!("*.yml"), format!("no specfile matches: "))]
+    #[case(format!("not_found.yml"), format!("failed to open specfile: "))]
+    #[trace]
+    fn test_load_error(#[case] pattern: String, #[case] expected: String) {
+        let dir = write_files(vec![]);
+        let patterns = vec![format!("{}", dir.path().join(pattern).display())];
+
+        match load(&patterns) {
+            Ok(_) => panic!("load should fail"),
+            Err(error) => assert_eq!(format!("{}", error), format!("{}{}", expected, patterns[0])),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_yaml::Value;
+use std::cell::Cell;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+// locate returns line and column of the node at path in yaml input.
+// serde_yaml does not expose locations of nodes, but it adds location of current node to an
+// error raised while deserializing the node, so locate raises an error at the node.
+pub fn locate(input: &str, path: &[Segment]) -> Option<Location> {
+    let found = Cell::new(false);
+    let seek = Seek {
+        path,
+        found: &found,
+    };
+    match seek.deserialize(serde_yaml::Deserializer::from_str(input)) {
+        Err(error) if found.get() => error.location().map(|location| Location {
+            line: location.line(),
+            column: location.column(),
+        }),
+        _ => None,
+    }
+}
+
+struct Seek<'a> {
+    path: &'a [Segment],
+    found: &'a Cell<bool>,
+}
+
+impl<'de> DeserializeSeed<'de> for Seek<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if self.path.is_empty() {
+            self.found.set(true);
+            deserializer.deserialize_any(Here)
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for Seek<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("mapping or sequence")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        while let Some(key) = map.next_key::<Value>()? {
+            match &self.path[0] {
+                Segment::Key(name) if key.as_str() == Some(name) => {
+                    return map.next_value_seed(Seek {
+                        path: &self.path[1..],
+                        found: self.found,
+                    });
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Err(de::Error::custom("path is not found"))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut index = 0;
+        loop {
+            match &self.path[0] {
+                Segment::Index(target) if index == *target => {
+                    return match seq.next_element_seed(Seek {
+                        path: &self.path[1..],
+                        found: self.found,
+                    })? {
+                        Some(()) => Ok(()),
+                        None => Err(de::Error::custom("path is not found")),
+                    };
+                }
+                _ => {
+                    if seq.next_element::<IgnoredAny>()?.is_none() {
+                        return Err(de::Error::custom("path is not found"));
+                    }
+                }
+            }
+            index += 1;
+        }
+    }
+}
+
+// Here rejects any node, so the error has location of the node.
+struct Here;
+
+impl<'de> Visitor<'de> for Here {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("nothing")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specfile::location::*;
+    use rstest::*;
+
+    const INPUT: &str = r#"version: 1
+specs:
+  - operator: GKEClusterStatus
+    project: project
+    status:
+      - Running
+      - Runing
+  - operator: GKENodePoolStatus
+    status: [Running]
+"#;
+
+    fn key(name: &str) -> Segment {
+        Segment::Key(format!("{}", name))
+    }
+
+    #[rstest]
+    #[case(vec![], Some(Location { line: 1, column: 1 }))]
+    #[case(vec![key("version")], Some(Location { line: 1, column: 10 }))]
+    #[case(vec![key("specs"), Segment::Index(0)], Some(Location { line: 3, column: 5 }))]
+    #[case(
+        vec![key("specs"), Segment::Index(0), key("status"), Segment::Index(1)],
+        Some(Location { line: 7, column: 9 })
+    )]
+    #[case(
+        vec![key("specs"), Segment::Index(1), key("status"), Segment::Index(0)],
+        Some(Location { line: 9, column: 14 })
+    )]
+    #[case(vec![key("specs"), Segment::Index(2)], None)]
+    #[case(vec![key("defaults")], None)]
+    #[case(vec![key("version"), key("value")], None)]
+    #[trace]
+    fn test_locate(#[case] path: Vec<Segment>, #[case] expected: Option<Location>) {
+        assert_eq!(locate(INPUT, &path), expected);
+    }
+}
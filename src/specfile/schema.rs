@@ -0,0 +1,277 @@
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Value};
+
+use crate::spec::Spec;
+use crate::specfile::settings::Settings;
+use crate::specfile::SPECFILE_VERSION;
+
+// schema returns JSON Schema of specfile, which is derived from definitions of specs.
+pub fn schema() -> Value {
+    let mut generator = SchemaSettings::draft07().into_generator();
+    let spec = generator.subschema_for::<Spec>();
+    let settings = generator.subschema_for::<Settings>();
+    let mut definitions = serde_json::to_value(generator.take_definitions()).unwrap();
+
+    // keys of specs in document can be given by defaults, so only operator is required.
+    let mut partial_spec = definitions["Spec"].clone();
+    retain_required(&mut partial_spec, "operator");
+    definitions["PartialSpec"] = partial_spec;
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "yubi specfile",
+        "oneOf": [
+            {
+                "type": "array",
+                "items": spec,
+            },
+            {
+                "type": "object",
+                "properties": {
+                    "version": { "const": SPECFILE_VERSION },
+                    "vars": {
+                        "type": "object",
+                        "additionalProperties": { "type": ["string", "number", "boolean"] },
+                    },
+                    "defaults": { "type": "object" },
+                    "settings": settings,
+                    "include": {
+                        "oneOf": [
+                            { "type": "string" },
+                            { "type": "array", "items": { "type": "string" } },
+                        ],
+                    },
+                    "specs": {
+                        "type": "array",
+                        "items": { "$ref": "#/definitions/PartialSpec" },
+                    },
+                },
+                "additionalProperties": false,
+                "anyOf": [
+                    { "required": ["specs"] },
+                    { "required": ["include"] },
+                ],
+            },
+        ],
+        "definitions": definitions,
+    })
+}
+
+fn retain_required(schema: &mut Value, key: &str) {
+    match schema {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = object.get_mut("required") {
+                required.retain(|required| required == key);
+            }
+            object
+                .values_mut()
+                .for_each(|value| retain_required(value, key));
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| retain_required(value, key)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specfile::schema::*;
+    use rstest::*;
+
+    fn operators(spec: &Value) -> Vec<(Value, Value)> {
+        spec["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| {
+                (
+                    variant["properties"]["operator"]["enum"].clone(),
+                    variant["required"].clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn test_schema_spec() {
+        let schema = schema();
+        let definitions = &schema["definitions"];
+
+        assert_eq!(
+            operators(&definitions["Spec"]),
+            vec![
+                (
+                    json!(["GKEClusterStatus"]),
+                    json!(["cluster", "location", "operator", "project", "status"])
+                ),
+                (
+                    json!(["GKENodePoolStatus"]),
+                    json!([
+                        "cluster",
+                        "location",
+                        "node_pool",
+                        "operator",
+                        "project",
+                        "status"
+                    ])
+                ),
+                (
+                    json!(["GKEClusterVersion"]),
+                    json!(["cluster", "location", "operator", "project", "version"])
+                ),
+                (
+                    json!(["GKENodePoolVersion"]),
+                    json!([
+                        "cluster",
+                        "location",
+                        "node_pool",
+                        "operator",
+                        "project",
+                        "version"
+                    ])
+                ),
+                (
+                    json!(["GKENodePoolAutoscaling"]),
+                    json!(["cluster", "location", "node_pool", "operator", "project"])
+                ),
+                (
+                    json!(["GKENodePoolSize"]),
+                    json!(["cluster", "location", "node_count", "operator", "project"])
+                ),
+                (
+                    json!(["GKENodePoolConfig"]),
+                    json!(["cluster", "location", "node_pool", "operator", "project"])
+                ),
+            ]
+        );
+        assert_eq!(
+            operators(&definitions["PartialSpec"]),
+            vec![
+                (json!(["GKEClusterStatus"]), json!(["operator"])),
+                (json!(["GKENodePoolStatus"]), json!(["operator"])),
+                (json!(["GKEClusterVersion"]), json!(["operator"])),
+                (json!(["GKENodePoolVersion"]), json!(["operator"])),
+                (json!(["GKENodePoolAutoscaling"]), json!(["operator"])),
+                (json!(["GKENodePoolSize"]), json!(["operator"])),
+                (json!(["GKENodePoolConfig"]), json!(["operator"])),
+            ]
+        );
+        assert_eq!(
+            definitions["Spec"]["properties"]["timeout"]["type"],
+            json!(["string", "null"])
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "ClusterStatus",
+        json!(["Unspecified", "Provisioning", "Running", "Reconciling", "Stopping", "Error", "Degraded"])
+    )]
+    #[case(
+        "NodePoolStatus",
+        json!(["Unspecified", "Provisioning", "Running", "RunningWithError", "Reconciling", "Stopping", "Error"])
+    )]
+    #[trace]
+    fn test_schema_status(#[case] name: &str, #[case] expected: Value) {
+        let status = &schema()["definitions"][name];
+
+        assert_eq!(status["anyOf"][0]["enum"], expected);
+        assert_eq!(
+            status["anyOf"][1]["pattern"],
+            json!("^Unknown\\(-?[0-9]+\\)$")
+        );
+    }
+
+    #[rstest]
+    fn test_schema_document() {
+        let schema = schema();
+        let document = &schema["oneOf"][1];
+
+        assert_eq!(document["properties"]["version"], json!({ "const": 1 }));
+        assert_eq!(
+            document["properties"]["settings"]["$ref"],
+            json!("#/definitions/Settings")
+        );
+        assert_eq!(
+            schema["definitions"]["Settings"]["properties"]["format"]["anyOf"][0]["$ref"],
+            json!("#/definitions/Format")
+        );
+        assert_eq!(
+            schema["definitions"]["Format"]["enum"],
+            json!(["yaml", "json", "junit"])
+        );
+    }
+
+    #[rstest]
+    #[case(
+        json!([{
+            "id": "prod-cluster",
+            "name": "production cluster is running",
+            "operator": "GKEClusterStatus",
+            "project": "project",
+            "location": "location",
+            "cluster": "cluster",
+            "status": ["Running"],
+            "tags": ["prod"],
+            "timeout": "30s",
+        }]),
+        true
+    )]
+    #[case(
+        json!({
+            "defaults": { "project": "project" },
+            "specs": [{
+                "id": "node-pool-size",
+                "operator": "GKENodePoolSize",
+                "node_count": { "min": 3 },
+                "tags": ["prod"],
+                "timeout": "1m",
+            }],
+        }),
+        true
+    )]
+    #[case(
+        json!([{
+            "operator": "GKENodePoolConfig",
+            "project": "project",
+            "location": "location",
+            "cluster": "cluster",
+            "node_pool": "node_pool",
+            "machine_typ": "e2-medium",
+            "tags": ["prod"],
+        }]),
+        false
+    )]
+    #[case(
+        json!([{
+            "operator": "GKEClusterStatus",
+            "project": "project",
+            "location": "location",
+            "cluster": "cluster",
+            "status": ["Running"],
+            "tag": ["prod"],
+        }]),
+        false
+    )]
+    #[case(
+        json!([{
+            "operator": "GKEClusterStatus",
+            "project": "project",
+            "location": "location",
+            "cluster": "cluster",
+            "status": ["Running"],
+            "tags": "prod",
+        }]),
+        false
+    )]
+    #[trace]
+    fn test_schema_validate(#[case] specfile: Value, #[case] expected: bool) {
+        let schema = jsonschema::JSONSchema::options()
+            .with_draft(jsonschema::Draft::Draft7)
+            .compile(&schema())
+            .unwrap();
+
+        assert_eq!(schema.is_valid(&specfile), expected);
+    }
+}
@@ -0,0 +1,195 @@
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use crate::report::format::Format;
+use crate::spec::scalar::option_from_str_or;
+
+// Settings are defaults of command line options, which are preferred over settings.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    #[serde(default, deserialize_with = "option_from_str_or")]
+    pub concurrency: Option<NonZeroUsize>,
+    #[serde(default, with = "humantime_serde")]
+    #[schemars(with = "Option<String>")]
+    pub timeout: Option<Duration>,
+    pub format: Option<Format>,
+    #[schemars(description = "URL of GKE API endpoint")]
+    pub endpoint: Option<String>,
+    #[schemars(description = "Connect to GKE API endpoint without TLS and authentication")]
+    #[serde(default, deserialize_with = "option_from_str_or")]
+    pub plaintext: Option<bool>,
+    #[schemars(
+        description = "Path to PEM encoded CA certificate of GKE API endpoint, relative to the specfile"
+    )]
+    pub ca_certificate: Option<String>,
+    #[schemars(description = "URL of Compute Engine API endpoint")]
+    pub compute_endpoint: Option<String>,
+}
+
+impl Settings {
+    // or returns settings which prefer values of self over values of other.
+    // plaintext and ca_certificate are taken together, because they both decide how to connect.
+    pub fn or(self, other: Settings) -> Settings {
+        let (plaintext, ca_certificate) =
+            if self.plaintext.is_some() || self.ca_certificate.is_some() {
+                (self.plaintext, self.ca_certificate)
+            } else {
+                (other.plaintext, other.ca_certificate)
+            };
+        Settings {
+            concurrency: self.concurrency.or(other.concurrency),
+            timeout: self.timeout.or(other.timeout),
+            format: self.format.or(other.format),
+            endpoint: self.endpoint.or(other.endpoint),
+            plaintext,
+            ca_certificate,
+            compute_endpoint: self.compute_endpoint.or(other.compute_endpoint),
+        }
+    }
+
+    // has_connection tells whether settings decide how to connect to API endpoints.
+    pub fn has_connection(&self) -> bool {
+        self.endpoint.is_some()
+            || self.plaintext.is_some()
+            || self.ca_certificate.is_some()
+            || self.compute_endpoint.is_some()
+    }
+
+    // validate_connection rejects settings of connection unless they are in the only specfile
+    // given on command line. credentials are sent to the endpoint, and other specfiles, like
+    // included ones or ones matched by a glob, may be owned by someone else.
+    pub fn validate_connection(&self, included: bool, top_level: usize) -> Result<()> {
+        if self.has_connection() && (included || top_level > 1) {
+            return Err(anyhow!(
+                "endpoint, plaintext, ca_certificate and compute_endpoint of settings can be given only when a single specfile is given on command line"
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.plaintext == Some(true) && self.ca_certificate.is_some() {
+            return Err(anyhow!(
+                "plaintext and ca_certificate can not be used together"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::specfile::settings::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(format!("{{}}"), Ok(Settings::default()))]
+    #[case(
+        format!("concurrency: 8\ntimeout: 5m\nformat: junit\n"),
+        Ok(Settings {
+            concurrency: NonZeroUsize::new(8),
+            timeout: Some(Duration::from_secs(300)),
+            format: Some(Format::Junit),
+            ..Default::default()
+        })
+    )]
+    #[case(
+        format!("endpoint: http://127.0.0.1:8080\nplaintext: true\ncompute_endpoint: http://127.0.0.1:8081/compute/v1\n"),
+        Ok(Settings {
+            endpoint: Some(format!("http://127.0.0.1:8080")),
+            plaintext: Some(true),
+            compute_endpoint: Some(format!("http://127.0.0.1:8081/compute/v1")),
+            ..Default::default()
+        })
+    )]
+    #[case(format!("concurrency: 0\n"), Err(()))]
+    #[case(format!("interval: 15s\n"), Err(()))]
+    #[trace]
+    fn test_deserialize(#[case] input: String, #[case] expected: Result<Settings, ()>) {
+        assert_eq!(
+            serde_yaml::from_str::<Settings>(&input).map_err(|_| ()),
+            expected
+        );
+    }
+
+    #[rstest]
+    fn test_or() {
+        let settings = Settings {
+            concurrency: NonZeroUsize::new(2),
+            endpoint: Some(format!("https://endpoint")),
+            ..Default::default()
+        };
+        let other = Settings {
+            concurrency: NonZeroUsize::new(8),
+            timeout: Some(Duration::from_secs(60)),
+            endpoint: Some(format!("https://other")),
+            compute_endpoint: Some(format!("https://other/compute/v1")),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.or(other),
+            Settings {
+                concurrency: NonZeroUsize::new(2),
+                timeout: Some(Duration::from_secs(60)),
+                endpoint: Some(format!("https://endpoint")),
+                compute_endpoint: Some(format!("https://other/compute/v1")),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[rstest]
+    #[case(None, None, Some(true), Some("ca.pem"), Some(true), Some("ca.pem"))]
+    #[case(Some(true), None, None, Some("ca.pem"), Some(true), None)]
+    #[case(None, Some("ca.pem"), Some(true), None, None, Some("ca.pem"))]
+    #[trace]
+    fn test_or_connection(
+        #[case] plaintext: Option<bool>,
+        #[case] ca_certificate: Option<&str>,
+        #[case] other_plaintext: Option<bool>,
+        #[case] other_ca_certificate: Option<&str>,
+        #[case] expected_plaintext: Option<bool>,
+        #[case] expected_ca_certificate: Option<&str>,
+    ) {
+        let settings = Settings {
+            plaintext,
+            ca_certificate: ca_certificate.map(|path| format!("{}", path)),
+            ..Default::default()
+        };
+        let other = Settings {
+            plaintext: other_plaintext,
+            ca_certificate: other_ca_certificate.map(|path| format!("{}", path)),
+            ..Default::default()
+        };
+
+        let settings = settings.or(other);
+        assert_eq!(settings.plaintext, expected_plaintext);
+        assert_eq!(
+            settings.ca_certificate,
+            expected_ca_certificate.map(|path| format!("{}", path))
+        );
+    }
+
+    #[rstest]
+    #[case(Some(true), Some("ca.pem"), false)]
+    #[case(Some(false), Some("ca.pem"), true)]
+    #[case(Some(true), None, true)]
+    #[trace]
+    fn test_validate(
+        #[case] plaintext: Option<bool>,
+        #[case] ca_certificate: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let settings = Settings {
+            plaintext,
+            ca_certificate: ca_certificate.map(|path| format!("{}", path)),
+            ..Default::default()
+        };
+        assert_eq!(settings.validate().is_ok(), expected);
+    }
+}
@@ -0,0 +1,575 @@
+use serde::de::DeserializeOwned;
+use serde_yaml::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::operator::registry::registry;
+use crate::spec::{Spec, COMMON_KEYS};
+use crate::specfile::loader::walk;
+use crate::specfile::location::{locate, Location, Segment};
+use crate::specfile::parse_document;
+
+// Diagnosis is the path of an invalid node in spec and why it is invalid.
+pub type Diagnosis = (Vec<Segment>, String);
+
+// Problem is an invalid part of specfile found by validate.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Problem {
+    pub file: Option<String>,
+    pub location: Option<Location>,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(file: Option<String>, location: Option<Location>, message: String) -> Problem {
+        Problem {
+            file,
+            location,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.file, &self.location) {
+            (Some(file), Some(location)) => write!(
+                f,
+                "{}:{}:{}: {}",
+                file, location.line, location.column, self.message
+            ),
+            (Some(file), None) => write!(f, "{}: {}", file, self.message),
+            (None, _) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Validation {
+    pub specfiles: usize,
+    pub specs: usize,
+    pub problems: Vec<Problem>,
+}
+
+impl Validation {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+// validate specfiles without calling GKE API. unlike loader, it does not stop at the first
+// invalid spec, and reports all problems with their locations.
+pub fn validate(patterns: &[String]) -> Validation {
+    validate_with_env(patterns, &|name| std::env::var(name).ok())
+}
+
+pub fn validate_with_env<F>(patterns: &[String], env: &F) -> Validation
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut validation = Validation::default();
+    // id and where it is defined first.
+    let mut ids = HashMap::<String, String>::new();
+    let mut top_level = 0;
+    // settings of connection are checked after all specfiles given on command line are counted,
+    // and their problems are inserted at the position of the specfile.
+    let mut connections = vec![];
+
+    let result = walk(patterns, |path, input, included| {
+        let file = format!("{}", path.display());
+        validation.specfiles += 1;
+        let document = match parse_document(input, env) {
+            Ok(document) => document,
+            Err(error) => {
+                let location = error
+                    .downcast_ref::<serde_yaml::Error>()
+                    .and_then(|error| error.location())
+                    .map(|location| Location {
+                        line: location.line(),
+                        column: location.column(),
+                    });
+                validation.problems.push(Problem::new(
+                    Some(file),
+                    location,
+                    format!("{:#}", error),
+                ));
+                return Ok(vec![]);
+            }
+        };
+
+        if !included {
+            top_level += 1;
+        }
+        connections.push((
+            validation.problems.len(),
+            file.clone(),
+            locate(input, &[Segment::Key(format!("settings"))]),
+            included,
+            document.settings.clone(),
+        ));
+
+        for (index, value) in document.specs.iter().enumerate() {
+            validation.specs += 1;
+            let spec_path = if document.bare {
+                vec![Segment::Index(index)]
+            } else {
+                vec![Segment::Key(format!("specs")), Segment::Index(index)]
+            };
+            // keys merged from defaults are not found in spec, so spec itself is located.
+            let locate_in_spec = |path: &[Segment]| {
+                locate(input, &[&spec_path[..], path].concat())
+                    .or_else(|| locate(input, &spec_path))
+            };
+
+            // id is read from spec itself, so that duplicate id is reported even if the spec has
+            // other problems.
+            if let Some(id) = value.get("id").and_then(Value::as_str) {
+                let location = locate_in_spec(&[Segment::Key(format!("id"))]);
+                match ids.get(id) {
+                    Some(first) => validation.problems.push(Problem::new(
+                        Some(file.clone()),
+                        location,
+                        format!("duplicate id: {}, first defined at {}", id, first),
+                    )),
+                    None => {
+                        let defined = match location {
+                            Some(location) => {
+                                format!("{}:{}:{}", file, location.line, location.column)
+                            }
+                            None => format!("{}", file),
+                        };
+                        ids.insert(format!("{}", id), defined);
+                    }
+                }
+            }
+
+            if let Err(problems) = check_spec(value) {
+                for (path, message) in problems {
+                    validation.problems.push(Problem::new(
+                        Some(file.clone()),
+                        locate_in_spec(&path),
+                        message,
+                    ));
+                }
+            }
+        }
+        Ok(document.includes)
+    });
+    for (index, file, location, included, settings) in connections.into_iter().rev() {
+        if let Err(error) = settings.validate_connection(included, top_level) {
+            validation.problems.insert(
+                index,
+                Problem::new(Some(file), location, format!("{}", error)),
+            );
+        }
+    }
+    if let Err(error) = result {
+        validation
+            .problems
+            .push(Problem::new(None, None, format!("{:#}", error)));
+    }
+    validation
+}
+
+// check_spec deserializes spec, or explains why spec is invalid with paths of the invalid nodes in
+// spec. unlike deserialization, unknown keys are invalid too.
+fn check_spec(spec: &Value) -> Result<Spec, Vec<Diagnosis>> {
+    if !spec.is_mapping() {
+        return Err(vec![(vec![], format!("spec must be a mapping"))]);
+    }
+    let operator = match spec.get("operator").map(|operator| operator.as_str()) {
+        None => return Err(vec![(vec![], format!("operator is not found"))]),
+        Some(None) => {
+            return Err(vec![(
+                vec![Segment::Key(format!("operator"))],
+                format!("operator must be a string"),
+            )])
+        }
+        Some(Some(operator)) => operator,
+    };
+    let mut rest = spec.clone();
+    // registry is released before the rest is deserialized, which reads registry again.
+    let mut problems = {
+        let registry = registry();
+        let fields = match registry.fields(operator) {
+            Some(fields) => fields,
+            None => {
+                let operators = registry.names();
+                return Err(vec![(
+                    vec![Segment::Key(format!("operator"))],
+                    suggest(
+                        format!("unknown operator: {}", operator),
+                        operator,
+                        &operators,
+                    ),
+                )]);
+            }
+        };
+        let mut problems = unknown_keys(&mut rest, &fields);
+        problems.extend(registry.diagnose(operator, &mut rest));
+        problems
+    };
+
+    // check the rest of spec, whose invalid nodes are removed or replaced.
+    match serde_yaml::from_value::<Spec>(rest.clone()) {
+        Ok(spec) if problems.is_empty() => Ok(spec),
+        Ok(_) => Err(problems),
+        Err(error) => {
+            let path = registry().error_path(operator, operator_config(rest));
+            problems.insert(0, (path, format!("{}", error)));
+            Err(problems)
+        }
+    }
+}
+
+// unknown_keys diagnoses keys of spec which are neither common keys nor fields of the operator,
+// and removes them from spec.
+fn unknown_keys(spec: &mut Value, fields: &[String]) -> Vec<Diagnosis> {
+    let spec = match spec.as_mapping_mut() {
+        Some(spec) => spec,
+        None => return vec![],
+    };
+    let known = COMMON_KEYS
+        .iter()
+        .copied()
+        .chain(["operator"])
+        .chain(fields.iter().map(String::as_str))
+        .collect::<Vec<&str>>();
+    let unknown = spec
+        .keys()
+        .filter(|key| !key.as_str().is_some_and(|key| known.contains(&key)))
+        .cloned()
+        .collect::<Vec<Value>>();
+    unknown
+        .into_iter()
+        .map(|key| {
+            spec.remove(&key);
+            match key.as_str() {
+                Some(key) => (
+                    vec![Segment::Key(format!("{}", key))],
+                    suggest(format!("unknown key: {}", key), key, &known),
+                ),
+                None => (vec![], format!("key must be a string")),
+            }
+        })
+        .collect()
+}
+
+// operator_config is spec without the keys which do not belong to operator.
+fn operator_config(mut spec: Value) -> Value {
+    if let Some(config) = spec.as_mapping_mut() {
+        for key in COMMON_KEYS.iter().chain(&["operator"]) {
+            config.remove(*key);
+        }
+    }
+    spec
+}
+
+// unknown_statuses diagnoses status of spec, which is a list of T, and replaces it with an empty
+// list if some of them are unknown.
+pub(crate) fn unknown_statuses<T>(spec: &mut Value, variants: &[&str]) -> Vec<Diagnosis>
+where
+    T: DeserializeOwned,
+{
+    let statuses = match spec.get("status").and_then(Value::as_sequence) {
+        Some(statuses) => statuses,
+        None => return vec![],
+    };
+    let problems = statuses
+        .iter()
+        .enumerate()
+        .filter(|(_, status)| serde_yaml::from_value::<T>((*status).clone()).is_err())
+        .map(|(index, status)| {
+            let path = vec![Segment::Key(format!("status")), Segment::Index(index)];
+            match status.as_str() {
+                Some(status) => (
+                    path,
+                    suggest(format!("unknown status: {}", status), status, variants),
+                ),
+                None => (path, format!("status must be a string")),
+            }
+        })
+        .collect::<Vec<Diagnosis>>();
+    if !problems.is_empty() {
+        spec["status"] = Value::Sequence(vec![]);
+    }
+    problems
+}
+
+// suggest appends the candidate closest to value to message, if it is close enough.
+pub(crate) fn suggest(message: String, value: &str, candidates: &[&str]) -> String {
+    let value = value.to_lowercase();
+    let closest = candidates
+        .iter()
+        .filter(|candidate| !candidate.contains('<'))
+        .map(|candidate| {
+            let distance = strsim::levenshtein(&value, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .min();
+    match closest {
+        Some((distance, candidate)) if distance <= (value.len() / 3).max(2) => {
+            format!("{}, did you mean {}?", message, candidate)
+        }
+        _ => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spec::cluster_status;
+    use crate::specfile::validation::*;
+    use rstest::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    // write_file writes content into temporary file, which is removed when it is dropped.
+    fn write_file(content: &str) -> NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "PROJECT" => Some(format!("project")),
+            _ => None,
+        }
+    }
+
+    #[rstest]
+    #[case::valid(
+        r#"- id: cluster
+  operator: GKEClusterStatus
+  project: ${PROJECT}
+  location: location
+  cluster: cluster
+  status:
+  - Running
+"#,
+        vec![]
+    )]
+    #[case::status(
+        r#"- operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+  - Runing
+  - Unknown(7)
+  - Deleting
+"#,
+        vec![
+            "7:5: unknown status: Runing, did you mean Running?",
+            "9:5: unknown status: Deleting",
+        ]
+    )]
+    #[case::operator(
+        r#"specs:
+  - operator: GKEClusterStatu
+    project: project
+  - operator: Cluster
+  - project: project
+"#,
+        vec![
+            "2:15: unknown operator: GKEClusterStatu, did you mean GKEClusterStatus?",
+            "4:15: unknown operator: Cluster",
+            "5:5: operator is not found",
+        ]
+    )]
+    #[case::missing(
+        r#"defaults:
+  project: project
+specs:
+  - operator: GKENodePoolStatus
+    location: location
+    cluster: cluster
+    status: [running]
+"#,
+        vec![
+            "4:5: missing field `node_pool`",
+            "7:14: unknown status: running, did you mean Running?",
+        ]
+    )]
+    #[case::unknown_key(
+        r#"defaults:
+  project: project
+  node_pool: node_pool
+specs:
+  - operator: GKEClusterStatus
+    location: location
+    cluster: cluster
+    clustr: cluster
+    statuses: [Running]
+    status: [Running]
+"#,
+        vec![
+            "8:13: unknown key: clustr, did you mean cluster?",
+            "9:15: unknown key: statuses, did you mean status?",
+        ]
+    )]
+    #[case::invalid_type(
+        r#"- operator: GKEClusterVersion
+  project: project
+  location: location
+  cluster: cluster
+  version: {min: 1}
+"#,
+        vec!["5:12: version constraint must be a string or an array of string"]
+    )]
+    #[case::invalid_nested_type(
+        r#"- operator: GKENodePoolSize
+  project: project
+  location: location
+  cluster: cluster
+  node_count:
+    min: many
+"#,
+        vec!["6:5: range must be a number or a mapping with min and max"]
+    )]
+    #[case::duplicate(
+        r#"- id: cluster
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status: [Running]
+- id: cluster
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status: [Running]
+"#,
+        vec!["7:7: duplicate id: cluster, first defined at {file}:1:7"]
+    )]
+    #[case::duplicate_invalid(
+        r#"- id: a
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status: [Running]
+- id: a
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status: [Runing]
+- id: a
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status: [Running]
+"#,
+        vec![
+            "7:7: duplicate id: a, first defined at {file}:1:7",
+            "12:12: unknown status: Runing, did you mean Running?",
+            "13:7: duplicate id: a, first defined at {file}:1:7",
+        ]
+    )]
+    #[case::syntax(
+        "- operator: GKEClusterStatus\n  status: [Running\n",
+        vec!["3:1: did not find expected ',' or ']' at line 3 column 1, while parsing a flow sequence at line 2 column 11"]
+    )]
+    #[case::variable(
+        "- operator: GKEClusterStatus\n  project: ${UNDEFINED}\n",
+        vec!["undefined variable: UNDEFINED"]
+    )]
+    #[trace]
+    fn test_validate(#[case] content: &str, #[case] expected: Vec<&str>) {
+        let path = write_file(content);
+        let file = format!("{}", path.path().display());
+
+        let validation = validate_with_env(std::slice::from_ref(&file), &env);
+
+        let expected = expected
+            .into_iter()
+            .map(|problem| {
+                let problem = problem.replace("{file}", &file);
+                if problem.starts_with(char::is_numeric) {
+                    format!("{}:{}", file, problem)
+                } else {
+                    format!("{}: {}", file, problem)
+                }
+            })
+            .collect::<Vec<String>>();
+        let problems = validation
+            .problems
+            .iter()
+            .map(|problem| format!("{}", problem))
+            .collect::<Vec<String>>();
+        assert_eq!(problems, expected);
+        assert_eq!(validation.specfiles, 1);
+    }
+
+    #[rstest]
+    fn test_validate_not_found() {
+        let validation = validate_with_env(&[format!("/path/to/not_found.yml")], &env);
+
+        assert_eq!(
+            validation.problems,
+            vec![Problem::new(
+                None,
+                None,
+                format!("failed to open specfile: /path/to/not_found.yml: No such file or directory (os error 2)")
+            )]
+        );
+    }
+
+    // team.yml is included by root.yml, or matched by a glob together with root.yml.
+    #[rstest]
+    #[case(
+        "root.yml",
+        "include: team.yml\nsettings:\n  endpoint: https://endpoint\n"
+    )]
+    #[case("*.yml", "specs: []\n")]
+    #[trace]
+    fn test_validate_endpoint(#[case] pattern: &str, #[case] root: &str) {
+        let dir = tempfile::tempdir().unwrap();
+        let team = dir.path().join("team.yml");
+        std::fs::write(dir.path().join("root.yml"), root).unwrap();
+        std::fs::write(
+            &team,
+            "settings:\n  endpoint: https://attacker.example.com\nspecs: []\n",
+        )
+        .unwrap();
+
+        let validation =
+            validate_with_env(&[format!("{}", dir.path().join(pattern).display())], &env);
+
+        assert_eq!(
+            validation
+                .problems
+                .iter()
+                .map(|problem| format!("{}", problem))
+                .collect::<Vec<String>>(),
+            vec![format!(
+                "{}:2:3: endpoint, plaintext, ca_certificate and compute_endpoint of settings can be given only when a single specfile is given on command line",
+                team.display()
+            )]
+        );
+        assert_eq!(validation.specfiles, 2);
+    }
+
+    #[rstest]
+    #[case("Runing", Some("Running"))]
+    #[case("running", Some("Running"))]
+    #[case("PROVISIONING", Some("Provisioning"))]
+    #[case("Stoping", Some("Stopping"))]
+    #[case("Deleting", None)]
+    #[case("Unknown", None)]
+    #[trace]
+    fn test_suggest(#[case] value: &str, #[case] expected: Option<&str>) {
+        let expected = match expected {
+            Some(candidate) => format!("message, did you mean {}?", candidate),
+            None => format!("message"),
+        };
+        assert_eq!(
+            suggest(format!("message"), value, cluster_status::VARIANTS),
+            expected
+        );
+    }
+}
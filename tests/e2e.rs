@@ -0,0 +1,993 @@
+mod support;
+
+use googapis::google::container::v1::{
+    Cluster, NodeConfig, NodePool, NodePoolAutoscaling, NodeTaint,
+};
+use rstest::*;
+use support::fake_cluster_manager::*;
+use support::fake_compute::*;
+use support::*;
+use tonic::Code;
+use yubi::client::endpoint::Endpoint;
+use yubi::client::gke_client::{GKEClient, GKEClientTrait};
+use yubi::client::retry::RetryPolicy;
+
+const SPECFILE: &str = r#"---
+- operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+    - Running
+- operator: GKENodePoolStatus
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  status:
+    - Running
+"#;
+
+fn fake_cluster_manager(cluster_status: i32, node_pool_status: i32) -> FakeClusterManager {
+    FakeClusterManager::new()
+        .cluster(
+            "project",
+            "location",
+            Cluster {
+                name: format!("cluster"),
+                status: cluster_status,
+                ..Default::default()
+            },
+        )
+        .node_pool(
+            "project",
+            "location",
+            "cluster",
+            NodePool {
+                name: format!("node_pool"),
+                status: node_pool_status,
+                ..Default::default()
+            },
+        )
+}
+
+async fn run_yubi_against(fake: &FakeClusterManager, args: Vec<&str>) -> YubiOutput {
+    run_specfile_against(fake, SPECFILE, args).await
+}
+
+// run_specfile_against starts fake and checks specfile against it.
+async fn run_specfile_against(
+    fake: &FakeClusterManager,
+    specfile: &str,
+    args: Vec<&str>,
+) -> YubiOutput {
+    let endpoint = fake.start().await;
+    let specfile = write_specfile(specfile);
+    let mut yubi_args = vec![
+        format!("--endpoint"),
+        endpoint,
+        format!("--plaintext"),
+        format!("--retry-backoff"),
+        format!("1ms"),
+    ];
+    yubi_args.extend(args.into_iter().map(|arg| format!("{}", arg)));
+    yubi_args.push(format!("{}", specfile.path().display()));
+    run_yubi(yubi_args).await
+}
+
+fn spec_results(output: &YubiOutput) -> Vec<(String, String)> {
+    output.report()["detail"]
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .map(|record| {
+            (
+                format!("{}", record["spec_result"]["code"].as_str().unwrap()),
+                format!("{}", record["spec_result"]["description"].as_str().unwrap()),
+            )
+        })
+        .collect()
+}
+
+#[rstest]
+#[case(
+    2,
+    2,
+    Some(0),
+    vec![
+        (format!("success"), format!("cluster is Running")),
+        (format!("success"), format!("node_pool is Running")),
+    ]
+)]
+#[case(
+    5,
+    3,
+    Some(1),
+    vec![
+        (format!("failure"), format!("cluster is Error")),
+        (format!("failure"), format!("node_pool is RunningWithError")),
+    ]
+)]
+#[case(
+    42,
+    43,
+    Some(2),
+    vec![
+        (format!("error"), format!("cluster is Unknown(42)")),
+        (format!("error"), format!("node_pool is Unknown(43)")),
+    ]
+)]
+#[trace]
+#[tokio::test]
+async fn test_check_status(
+    #[case] cluster_status: i32,
+    #[case] node_pool_status: i32,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_results: Vec<(String, String)>,
+) {
+    let fake = fake_cluster_manager(cluster_status, node_pool_status);
+    let output = run_yubi_against(&fake, vec![]).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    assert_eq!(spec_results(&output), expected_results);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_requests() {
+    let fake = fake_cluster_manager(2, 2);
+    let output = run_yubi_against(&fake, vec!["--concurrency", "1"]).await;
+
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(
+        fake.requests(),
+        vec![
+            FakeRequest {
+                method: format!("GetCluster"),
+                name: format!("projects/project/locations/location/clusters/cluster"),
+                authorization: None,
+            },
+            FakeRequest {
+                method: format!("GetNodePool"),
+                name: format!(
+                    "projects/project/locations/location/clusters/cluster/nodePools/node_pool"
+                ),
+                authorization: None,
+            },
+        ]
+    );
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_not_found() {
+    let fake = FakeClusterManager::new();
+    let output = run_yubi_against(&fake, vec![]).await;
+
+    assert_eq!(output.code, Some(2), "{}", output.stderr);
+    assert_eq!(
+        spec_results(&output),
+        vec![
+            (
+                format!("error"),
+                format!("projects/project/locations/location/clusters/cluster not found")
+            ),
+            (
+                format!("error"),
+                format!(
+                    "projects/project/locations/location/clusters/cluster/nodePools/node_pool not found"
+                )
+            ),
+        ]
+    );
+}
+
+#[rstest]
+#[case(
+    vec![Code::Unavailable, Code::DeadlineExceeded],
+    vec![],
+    Some(0),
+    (format!("success"), format!("cluster is Running (retry count: 2)"))
+)]
+#[case(
+    vec![Code::Unavailable, Code::Unavailable],
+    vec!["--retries", "1"],
+    Some(2),
+    (
+        format!("error"),
+        format!(
+            "scripted failure of projects/project/locations/location/clusters/cluster (retry count: 1)"
+        )
+    )
+)]
+#[case(
+    vec![Code::PermissionDenied],
+    vec![],
+    Some(2),
+    (
+        format!("error"),
+        format!("scripted failure of projects/project/locations/location/clusters/cluster")
+    )
+)]
+#[trace]
+#[tokio::test]
+async fn test_retry(
+    #[case] failures: Vec<Code>,
+    #[case] args: Vec<&str>,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_result: (String, String),
+) {
+    let fake =
+        fake_cluster_manager(2, 2).fail(&cluster_name("project", "location", "cluster"), failures);
+    let output = run_yubi_against(&fake, args).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    assert_eq!(spec_results(&output)[0], expected_result);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_format_json() {
+    let fake = fake_cluster_manager(2, 2);
+    let output = run_yubi_against(&fake, vec!["--format", "json"]).await;
+
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    let report = serde_json::from_str::<serde_json::Value>(&output.stdout).unwrap();
+    assert_eq!(report["summary"]["total"], 2);
+    assert_eq!(report["summary"]["success"], 2);
+    assert_eq!(report["detail"][0]["spec"]["operator"], "GKEClusterStatus");
+    assert_eq!(report["detail"][1]["spec_result"]["code"], "success");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_format_junit() {
+    let fake = fake_cluster_manager(5, 2);
+    let endpoint = fake.start().await;
+    // node pool name with tab and escape is not found, and is shown in error message.
+    let specfile = write_specfile(&format!(
+        "{}{}",
+        SPECFILE,
+        r#"- operator: GKENodePoolStatus
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: "<missing>\t\e"
+  status:
+    - Running
+"#
+    ));
+    let output = run_yubi(vec![
+        format!("--endpoint"),
+        endpoint,
+        format!("--plaintext"),
+        format!("--format"),
+        format!("junit"),
+        format!("{}", specfile.path().display()),
+    ])
+    .await;
+
+    assert_eq!(output.code, Some(2), "{}", output.stderr);
+    assert_eq!(
+        output.stdout.trim_end(),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites tests=\"3\" failures=\"1\" errors=\"1\">
+  <testsuite name=\"yubi\" tests=\"3\" failures=\"1\" errors=\"1\">
+    <testcase classname=\"GKEClusterStatus\" name=\"project/location/cluster\">
+      <failure message=\"cluster is Error\"/>
+    </testcase>
+    <testcase classname=\"GKENodePoolStatus\" name=\"project/location/cluster/node_pool\"/>
+    <testcase classname=\"GKENodePoolStatus\" name=\"project/location/cluster/&lt;missing&gt;&#9;\u{fffd}\">
+      <error message=\"projects/project/locations/location/clusters/cluster/nodePools/&lt;missing&gt;&#9;\u{fffd} not found\"/>
+    </testcase>
+  </testsuite>
+</testsuites>"
+    );
+}
+
+#[rstest]
+#[case(format!("- operator: GKEClusterStatus\n  project: project\n"))]
+#[case(format!("- operator: UnknownOperator\n"))]
+#[case(format!("not a list of specs"))]
+#[trace]
+#[tokio::test]
+async fn test_invalid_specfile(#[case] specfile: String) {
+    let specfile = write_specfile(&specfile);
+    let output = run_yubi(vec![format!("{}", specfile.path().display())]).await;
+
+    assert_eq!(output.code, Some(3), "{}", output.stderr);
+    assert!(
+        output.stderr.contains("failed to parse specfile"),
+        "{}",
+        output.stderr
+    );
+    assert_eq!(output.stdout, "");
+}
+
+#[rstest]
+#[case(vec![format!("/path/to/not_found.yml")], Some(3))]
+#[case(vec![format!("--concurrency"), format!("0"), format!("spec.yml")], Some(3))]
+#[case(vec![format!("--wait"), format!("spec.yml")], Some(3))]
+#[case(vec![format!("--version")], Some(0))]
+#[trace]
+#[tokio::test]
+async fn test_invalid_args(#[case] args: Vec<String>, #[case] expected_code: Option<i32>) {
+    let output = run_yubi(args).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+}
+
+#[rstest]
+#[case(vec![], true)]
+#[case(vec!["--format", "yaml"], false)]
+#[trace]
+#[tokio::test]
+async fn test_settings(#[case] args: Vec<&str>, #[case] expected_json: bool) {
+    let fake = fake_cluster_manager(2, 2);
+    let endpoint = fake.start().await;
+    let specfile = write_specfile(&format!(
+        r#"version: 1
+defaults:
+  project: project
+  location: location
+  cluster: cluster
+settings:
+  format: json
+specs:
+- operator: GKEClusterStatus
+  status:
+  - Running
+"#
+    ));
+    let mut yubi_args = vec![format!("--endpoint"), endpoint, format!("--plaintext")];
+    yubi_args.extend(args.into_iter().map(|arg| format!("{}", arg)));
+    yubi_args.push(format!("{}", specfile.path().display()));
+    let output = run_yubi(yubi_args).await;
+
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(output.stdout.starts_with('{'), expected_json);
+    assert_eq!(
+        spec_results(&output),
+        vec![(format!("success"), format!("cluster is Running"))]
+    );
+}
+
+// endpoint of settings is used without --endpoint, and --endpoint is preferred over it.
+#[rstest]
+#[case(vec![], Some(0))]
+#[case(vec!["--endpoint", "http://127.0.0.1:1", "--retries", "0"], Some(2))]
+#[trace]
+#[tokio::test]
+async fn test_settings_endpoint(#[case] args: Vec<&str>, #[case] expected_code: Option<i32>) {
+    let fake = fake_cluster_manager(2, 2);
+    let endpoint = fake.start().await;
+    let specfile = write_specfile(&format!(
+        r#"settings:
+  endpoint: {}
+  plaintext: true
+specs:
+- operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+"#,
+        endpoint
+    ));
+    let mut yubi_args = args
+        .into_iter()
+        .map(|arg| format!("{}", arg))
+        .collect::<Vec<String>>();
+    yubi_args.push(format!("{}", specfile.path().display()));
+    let output = run_yubi(yubi_args).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    assert_eq!(fake.requests().len(), usize::from(expected_code == Some(0)));
+}
+
+#[rstest]
+#[case(vec![], vec![format!("cluster"), format!("node_pool")])]
+#[case(vec!["--id", "node_pool"], vec![format!("node_pool")])]
+#[case(vec!["--tag", "cluster"], vec![format!("cluster")])]
+#[case(vec!["--skip-tag", "slow"], vec![format!("cluster")])]
+#[trace]
+#[tokio::test]
+async fn test_filter(#[case] args: Vec<&str>, #[case] expected_ids: Vec<String>) {
+    let fake = fake_cluster_manager(2, 2);
+    let endpoint = fake.start().await;
+    let specfile = write_specfile(&format!(
+        r#"- id: cluster
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+  tags:
+  - prod
+  - cluster
+- id: node_pool
+  operator: GKENodePoolStatus
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  status:
+  - Running
+  tags:
+  - prod
+  - slow
+"#
+    ));
+    let mut yubi_args = vec![format!("--endpoint"), endpoint, format!("--plaintext")];
+    yubi_args.extend(args.into_iter().map(|arg| format!("{}", arg)));
+    yubi_args.push(format!("{}", specfile.path().display()));
+    let output = run_yubi(yubi_args).await;
+
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    let ids = output.report()["detail"]
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .map(|record| format!("{}", record["spec"]["id"].as_str().unwrap()))
+        .collect::<Vec<String>>();
+    assert_eq!(ids, expected_ids);
+}
+
+// filter which selects no spec is an error, not an empty report.
+#[rstest]
+#[case(vec!["--id", "nodepool"], "no spec matches --id nodepool")]
+#[case(
+    vec!["--tag", "cluster", "--skip-tag", "prod"],
+    "no spec matches --tag cluster, --skip-tag prod"
+)]
+#[trace]
+#[tokio::test]
+async fn test_filter_no_spec(#[case] args: Vec<&str>, #[case] expected_error: &str) {
+    let fake = fake_cluster_manager(2, 2);
+    let specfile = r#"- id: cluster
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+  tags:
+  - prod
+  - cluster
+"#;
+
+    let output = run_specfile_against(&fake, specfile, args).await;
+
+    assert_eq!(output.code, Some(3), "{}", output.stderr);
+    assert!(output.stderr.contains(expected_error), "{}", output.stderr);
+    assert_eq!(output.stdout, "");
+    assert_eq!(fake.requests(), vec![]);
+}
+
+#[rstest]
+#[case(SPECFILE, Some(0), vec![format!("2 specs in 1 specfiles are valid")])]
+#[case(
+    "- operator: GKEClusterStatus\n  project: project\n  location: location\n  cluster: cluster\n  status: [Runing]\n",
+    Some(3),
+    vec![
+        format!("{{file}}:5:12: unknown status: Runing, did you mean Running?"),
+        format!("found 1 problems in 1 specfiles"),
+    ]
+)]
+#[trace]
+#[tokio::test]
+async fn test_validate(
+    #[case] specfile: &str,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_lines: Vec<String>,
+) {
+    // validate does not call GKE API, so no endpoint is given.
+    let specfile = write_specfile(specfile);
+    let file = format!("{}", specfile.path().display());
+    let output = run_yubi(vec![format!("validate"), file.clone()]).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    let expected_lines = expected_lines
+        .into_iter()
+        .map(|line| line.replace("{file}", &file))
+        .collect::<Vec<String>>();
+    assert_eq!(output.stdout.lines().collect::<Vec<&str>>(), expected_lines);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_init() {
+    let fake = fake_cluster_manager(2, 2)
+        .cluster(
+            "project",
+            "location-2",
+            Cluster {
+                name: format!("cluster-2"),
+                status: 3,
+                ..Default::default()
+            },
+        )
+        .cluster(
+            "other-project",
+            "location",
+            Cluster {
+                name: format!("other-cluster"),
+                status: 2,
+                ..Default::default()
+            },
+        );
+    let endpoint = fake.start().await;
+    let output = run_yubi(vec![
+        format!("init"),
+        format!("--project"),
+        format!("project"),
+        format!("--endpoint"),
+        endpoint.clone(),
+        format!("--plaintext"),
+    ])
+    .await;
+
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(
+        output.stdout,
+        format!(
+            r#"version: 1
+specs:
+- id: location/cluster
+  operator: GKEClusterStatus
+  project: project
+  location: location
+  cluster: cluster
+  status:
+  - Running
+- id: location/cluster/node_pool
+  operator: GKENodePoolStatus
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  status:
+  - Running
+- id: location-2/cluster-2
+  operator: GKEClusterStatus
+  project: project
+  location: location-2
+  cluster: cluster-2
+  status:
+  - Running
+"#
+        )
+    );
+    assert_eq!(
+        fake.requests()
+            .into_iter()
+            .map(|request| (request.method, request.name))
+            .collect::<Vec<(String, String)>>(),
+        vec![
+            (
+                format!("ListClusters"),
+                format!("projects/project/locations/-")
+            ),
+            (
+                format!("ListNodePools"),
+                format!("projects/project/locations/location/clusters/cluster")
+            ),
+            (
+                format!("ListNodePools"),
+                format!("projects/project/locations/location-2/clusters/cluster-2")
+            ),
+        ]
+    );
+
+    // generated specfile checks the clusters, and cluster-2 is not Running now.
+    let specfile = write_specfile(&output.stdout);
+    let output = run_yubi(vec![
+        format!("--endpoint"),
+        endpoint,
+        format!("--plaintext"),
+        format!("{}", specfile.path().display()),
+    ])
+    .await;
+    assert_eq!(output.code, Some(1), "{}", output.stderr);
+}
+
+#[rstest]
+#[case(format!("'>=1.27, <1.30'"), Some(0), (format!("success"), format!("cluster is 1.29.1-gke.300")))]
+#[case(format!("[\"1.27\", \"1.28\"]"), Some(1), (format!("failure"), format!("cluster is 1.29.1-gke.300, expected one of 1.27, 1.28")))]
+#[trace]
+#[tokio::test]
+async fn test_cluster_version(
+    #[case] version: String,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_result: (String, String),
+) {
+    let fake = FakeClusterManager::new().cluster(
+        "project",
+        "location",
+        Cluster {
+            name: format!("cluster"),
+            status: 2,
+            current_master_version: format!("1.29.1-gke.300"),
+            ..Default::default()
+        },
+    );
+    let specfile = format!(
+        r#"- operator: GKEClusterVersion
+  project: project
+  location: location
+  cluster: cluster
+  version: {}
+"#,
+        version
+    );
+
+    let output = run_specfile_against(&fake, &specfile, vec![]).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    assert_eq!(spec_results(&output), vec![expected_result]);
+}
+
+#[rstest]
+#[case(
+    "",
+    Some(0),
+    (format!("success"), format!("node_pool is 1.27.3-gke.100")),
+    vec![format!("GetNodePool")]
+)]
+#[case(
+    "  max_minor_skew: 1\n",
+    Some(1),
+    (format!("failure"), format!("node_pool is 1.27.3-gke.100, 2 minor versions behind master 1.29.1-gke.300, expected at most 1")),
+    vec![format!("GetNodePool"), format!("GetCluster")]
+)]
+#[trace]
+#[tokio::test]
+async fn test_node_pool_version(
+    #[case] max_minor_skew: &str,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_result: (String, String),
+    #[case] expected_methods: Vec<String>,
+) {
+    let fake = FakeClusterManager::new()
+        .cluster(
+            "project",
+            "location",
+            Cluster {
+                name: format!("cluster"),
+                current_master_version: format!("1.29.1-gke.300"),
+                ..Default::default()
+            },
+        )
+        .node_pool(
+            "project",
+            "location",
+            "cluster",
+            NodePool {
+                name: format!("node_pool"),
+                version: format!("1.27.3-gke.100"),
+                ..Default::default()
+            },
+        );
+    let specfile = format!(
+        r#"- operator: GKENodePoolVersion
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  version: '>=1.27'
+{}"#,
+        max_minor_skew
+    );
+
+    let output = run_specfile_against(&fake, &specfile, vec![]).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    assert_eq!(spec_results(&output), vec![expected_result]);
+    let methods = fake
+        .requests()
+        .into_iter()
+        .map(|request| request.method)
+        .collect::<Vec<String>>();
+    assert_eq!(methods, expected_methods);
+}
+
+#[rstest]
+#[case(
+    "min_node_count: 1\n  max_node_count:\n    min: 5\n",
+    Some(0),
+    Some((format!("success"), format!("node_pool autoscaling is enabled with 1-5 nodes")))
+)]
+#[case(
+    "max_node_count:\n    min: 10\n",
+    Some(1),
+    Some((format!("failure"), format!("node_pool autoscaling is enabled with 1-5 nodes, expected max_node_count at least 10")))
+)]
+#[case("enabled: false\n", Some(1), Some((format!("failure"), format!("node_pool autoscaling is enabled with 1-5 nodes, expected disabled"))))]
+#[case("enabled: false\n  max_node_count: 5\n", Some(3), None)]
+#[case(
+    "location_policy: any\n",
+    Some(1),
+    Some((format!("failure"), format!("node_pool autoscaling is enabled with 1-5 nodes, location_policy BALANCED, expected location_policy any")))
+)]
+#[case("max_nodes: 3\n", Some(3), None)]
+#[trace]
+#[tokio::test]
+async fn test_node_pool_autoscaling(
+    #[case] expectations: &str,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_result: Option<(String, String)>,
+) {
+    let fake = FakeClusterManager::new()
+        .node_pool(
+            "project",
+            "location",
+            "cluster",
+            NodePool {
+                name: format!("node_pool"),
+                autoscaling: Some(NodePoolAutoscaling {
+                    enabled: true,
+                    min_node_count: 1,
+                    max_node_count: 5,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .node_pool_detail(
+            "project",
+            "location",
+            "cluster",
+            "node_pool",
+            serde_json::json!({"autoscaling": {"enabled": true, "locationPolicy": "BALANCED"}}),
+        );
+    let specfile = format!(
+        r#"- operator: GKENodePoolAutoscaling
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  {}"#,
+        expectations
+    );
+
+    let output = run_specfile_against(&fake, &specfile, vec![]).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    match expected_result {
+        Some(expected_result) => assert_eq!(spec_results(&output), vec![expected_result]),
+        // spec which can not be checked is rejected before any request.
+        None => {
+            assert!(
+                output.stderr.contains("failed to parse specfile"),
+                "{}",
+                output.stderr
+            );
+            assert_eq!(fake.requests(), vec![]);
+        }
+    }
+}
+
+#[rstest]
+#[case(
+    "  node_pool: node_pool\n",
+    vec!["zone-a", "zone-b"],
+    Some(0),
+    (format!("success"), format!("node_pool has 3 nodes"))
+)]
+#[case(
+    "  node_pool: node_pool\n",
+    vec![],
+    Some(1),
+    (format!("failure"), format!("node_pool has 0 nodes, expected at least 3"))
+)]
+#[case(
+    "  node_pool: node_pool\n",
+    vec!["zone-a", "zone-c"],
+    Some(2),
+    (format!("error"), format!("node_pool has unknown number of nodes: The resource '/compute/v1/projects/project/zones/zone-c/instanceGroups/grp' was not found"))
+)]
+#[case("", vec![], Some(0), (format!("success"), format!("cluster has 4 nodes")))]
+#[trace]
+#[tokio::test]
+async fn test_node_pool_size(
+    #[case] node_pool: &str,
+    #[case] zones: Vec<&str>,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_result: (String, String),
+) {
+    #[allow(deprecated)]
+    let cluster = Cluster {
+        name: format!("cluster"),
+        current_node_count: 4,
+        ..Default::default()
+    };
+    let fake = FakeClusterManager::new()
+        .cluster("project", "location", cluster)
+        .node_pool(
+            "project",
+            "location",
+            "cluster",
+            NodePool {
+                name: format!("node_pool"),
+                initial_node_count: 5,
+                instance_group_urls: zones
+                    .iter()
+                    .map(|zone| instance_group_url("project", zone, "grp"))
+                    .collect(),
+                ..Default::default()
+            },
+        );
+    let compute = FakeCompute::new()
+        .instance_group("project", "zone-a", "grp", 2)
+        .instance_group("project", "zone-b", "grp", 1);
+    let compute_endpoint = compute.start().await;
+    let specfile = format!(
+        r#"- operator: GKENodePoolSize
+  project: project
+  location: location
+  cluster: cluster
+{}  node_count:
+    min: 3
+"#,
+        node_pool
+    );
+
+    let output = run_specfile_against(
+        &fake,
+        &specfile,
+        vec!["--compute-endpoint", &compute_endpoint],
+    )
+    .await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    assert_eq!(spec_results(&output), vec![expected_result]);
+    let requests = compute.requests();
+    assert_eq!(requests.len(), zones.len());
+    // plaintext endpoint is not authenticated, neither is compute endpoint.
+    assert!(requests
+        .iter()
+        .all(|request| request.method == "GET" && request.authorization.is_none()));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_compute_endpoint_http() {
+    let compute = FakeCompute::new().instance_group("project", "zone-a", "grp", 2);
+    let compute_endpoint = compute.start().await;
+    // GKE API endpoint is authenticated, but the token is not sent to plain http.
+    let client = GKEClient::new(Endpoint::default(), RetryPolicy::default())
+        .with_compute_endpoint(compute_endpoint);
+
+    let fetched = client
+        .fetch_instance_group_size(&instance_group_url("project", "zone-a", "grp"))
+        .await
+        .unwrap();
+
+    assert_eq!(fetched.value, 2);
+    assert!(compute
+        .requests()
+        .iter()
+        .all(|request| request.authorization.is_none()));
+}
+
+#[rstest]
+#[case(
+    "machine_type: e2-standard-4\n  taints:\n    - key: dedicated\n      value: batch\n      effect: NoSchedule\n",
+    Some(0),
+    Some((format!("success"), format!("node_pool has machine_type e2-standard-4, taints [dedicated=batch:NoSchedule]")))
+)]
+#[case(
+    "disk_size_gb:\n    min: 200\n  labels:\n    env: dev\n",
+    Some(1),
+    Some((format!("failure"), format!("node_pool has disk_size_gb 100, labels.env prod, expected disk_size_gb at least 200, expected labels.env dev")))
+)]
+#[case(
+    "preemptible: false\n  spot: true\n",
+    Some(0),
+    Some((format!("success"), format!("node_pool has preemptible false, spot true")))
+)]
+#[case("machine_typ: e2-standard-4\n", Some(3), None)]
+#[trace]
+#[tokio::test]
+async fn test_node_pool_config(
+    #[case] expectations: &str,
+    #[case] expected_code: Option<i32>,
+    #[case] expected_result: Option<(String, String)>,
+) {
+    let fake = FakeClusterManager::new()
+        .node_pool(
+            "project",
+            "location",
+            "cluster",
+            NodePool {
+                name: format!("node_pool"),
+                config: Some(NodeConfig {
+                    machine_type: format!("e2-standard-4"),
+                    disk_size_gb: 100,
+                    labels: [(format!("env"), format!("prod"))].into_iter().collect(),
+                    taints: vec![NodeTaint {
+                        key: format!("dedicated"),
+                        value: format!("batch"),
+                        effect: 1,
+                    }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .node_pool_detail(
+            "project",
+            "location",
+            "cluster",
+            "node_pool",
+            serde_json::json!({"config": {"spot": true}}),
+        );
+    let specfile = format!(
+        r#"- operator: GKENodePoolConfig
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  {}"#,
+        expectations
+    );
+
+    let output = run_specfile_against(&fake, &specfile, vec![]).await;
+
+    assert_eq!(output.code, expected_code, "{}", output.stderr);
+    match expected_result {
+        Some(expected_result) => assert_eq!(spec_results(&output), vec![expected_result]),
+        // spec which can not be checked is rejected before any request.
+        None => {
+            assert!(
+                output.stderr.contains("failed to parse specfile"),
+                "{}",
+                output.stderr
+            );
+            assert_eq!(fake.requests(), vec![]);
+        }
+    }
+}
+
+// spot is fetched from GKE REST API on the same endpoint after GetNodePool of gRPC.
+#[rstest]
+#[tokio::test]
+async fn test_node_pool_config_spot() {
+    let name = node_pool_name("project", "location", "cluster", "node_pool");
+    let fake = FakeClusterManager::new().node_pool(
+        "project",
+        "location",
+        "cluster",
+        NodePool {
+            name: format!("node_pool"),
+            ..Default::default()
+        },
+    );
+    let specfile = r#"- operator: GKENodePoolConfig
+  project: project
+  location: location
+  cluster: cluster
+  node_pool: node_pool
+  spot: false
+"#;
+
+    let output = run_specfile_against(&fake, specfile, vec![]).await;
+
+    assert_eq!(output.code, Some(0), "{}", output.stderr);
+    assert_eq!(
+        spec_results(&output),
+        vec![(format!("success"), format!("node_pool has spot false"))]
+    );
+    assert_eq!(
+        fake.requests()
+            .into_iter()
+            .map(|request| (request.method, request.name))
+            .collect::<Vec<(String, String)>>(),
+        vec![
+            (format!("GetNodePool"), name.clone()),
+            (format!("GET"), name.clone()),
+        ]
+    );
+}
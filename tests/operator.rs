@@ -0,0 +1,96 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rstest::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use yubi::client::gke_client::{GKEClient, GKEClientTrait};
+use yubi::operator::registry::register;
+use yubi::operator::Operator;
+use yubi::report::format::Format;
+use yubi::report::Report;
+use yubi::spec::result::SpecResult;
+use yubi::specfile;
+
+// ThresholdOperator is an operator defined outside of yubi, which does not call GKE API.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct ThresholdOperator {
+    value: u32,
+    max: u32,
+}
+
+#[async_trait]
+impl Operator for ThresholdOperator {
+    const NAME: &'static str = "Threshold";
+
+    fn target(&self) -> String {
+        format!("threshold")
+    }
+
+    async fn check(&self, _client: &Arc<dyn GKEClientTrait>) -> Result<SpecResult> {
+        let description = format!("{} of {}", self.value, self.max);
+        if self.value <= self.max {
+            Ok(SpecResult::Success { description })
+        } else {
+            Ok(SpecResult::Failure { description })
+        }
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_register_operator() {
+    register::<ThresholdOperator>();
+
+    let specfile = specfile::parse(
+        r#"
+- id: low
+  operator: Threshold
+  value: 1
+  max: 3
+- operator: Threshold
+  value: 5
+  max: 3
+  tags: [high]
+"#,
+    )
+    .unwrap();
+    let client: Arc<dyn GKEClientTrait> = Arc::new(GKEClient::default());
+
+    let mut report = Report::new();
+    for spec in specfile.specs {
+        let spec_result = spec.check(&client).await.unwrap();
+        report.record_ok(spec, spec_result);
+    }
+
+    assert_eq!(
+        Format::Yaml.render(&report).unwrap(),
+        format!(
+            r#"summary:
+  total: 2
+  success: 1
+  failure: 1
+  error: 0
+detail:
+- spec:
+    id: low
+    operator: Threshold
+    value: 1
+    max: 3
+  spec_result:
+    code: success
+    description: 1 of 3
+- spec:
+    operator: Threshold
+    value: 5
+    max: 3
+    tags:
+    - high
+  spec_result:
+    code: failure
+    description: 5 of 3
+"#
+        )
+    );
+    assert!(specfile::parse("- operator: Threshold\n  value: 1\n").is_err());
+}
@@ -0,0 +1,377 @@
+// tonic::Status is large, but it is the error type of every gRPC handler.
+#![allow(clippy::result_large_err)]
+
+use googapis::google::container::v1::{
+    Cluster, GetClusterRequest, GetNodePoolRequest, ListClustersRequest, ListClustersResponse,
+    ListNodePoolsRequest, ListNodePoolsResponse, NodePool,
+};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::Server;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{empty_body, http, Body, BoxFuture, Context, Never, Poll, Service, StdError};
+use tonic::server::Grpc;
+use tonic::{Code, Request, Response, Status};
+
+// FakeRequest is a request received by FakeClusterManager.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FakeRequest {
+    pub method: String,
+    pub name: String,
+    pub authorization: Option<String>,
+}
+
+#[derive(Default)]
+struct State {
+    clusters: BTreeMap<String, Cluster>,
+    node_pools: BTreeMap<String, NodePool>,
+    node_pool_details: BTreeMap<String, serde_json::Value>,
+    failures: HashMap<String, VecDeque<Code>>,
+    requests: Vec<FakeRequest>,
+}
+
+// FakeClusterManager is an in-process ClusterManager gRPC server which serves scripted
+// clusters and node pools. Only the methods used by yubi are implemented. GetNodePool of GKE REST
+// API is served on the same port, which returns fields missing in googapis.
+#[derive(Default, Clone)]
+pub struct FakeClusterManager {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeClusterManager {
+    pub fn new() -> FakeClusterManager {
+        FakeClusterManager::default()
+    }
+
+    pub fn cluster(self, project: &str, location: &str, cluster: Cluster) -> FakeClusterManager {
+        let name = cluster_name(project, location, &cluster.name);
+        let cluster = Cluster {
+            location: format!("{}", location),
+            ..cluster
+        };
+        self.state.lock().unwrap().clusters.insert(name, cluster);
+        self
+    }
+
+    pub fn node_pool(
+        self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: NodePool,
+    ) -> FakeClusterManager {
+        let name = node_pool_name(project, location, cluster, &node_pool.name);
+        self.state
+            .lock()
+            .unwrap()
+            .node_pools
+            .insert(name, node_pool);
+        self
+    }
+
+    // node_pool_detail is the json of node pool returned by GKE REST API, like
+    // {"config": {"spot": true}}. node pool without detail is returned as {}.
+    pub fn node_pool_detail(
+        self,
+        project: &str,
+        location: &str,
+        cluster: &str,
+        node_pool: &str,
+        detail: serde_json::Value,
+    ) -> FakeClusterManager {
+        let name = node_pool_name(project, location, cluster, node_pool);
+        self.state
+            .lock()
+            .unwrap()
+            .node_pool_details
+            .insert(name, detail);
+        self
+    }
+
+    // requests for the resource name fail with the codes in order before it is served.
+    pub fn fail(self, name: &str, codes: Vec<Code>) -> FakeClusterManager {
+        self.state
+            .lock()
+            .unwrap()
+            .failures
+            .insert(format!("{}", name), codes.into());
+        self
+    }
+
+    pub fn requests(&self) -> Vec<FakeRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    // start serves on a random local port and returns the endpoint url.
+    pub async fn start(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let fake = self.clone();
+        let make_service = make_service_fn(move |_| {
+            let fake = fake.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: http::Request<hyper::Body>| {
+                    let mut fake = fake.clone();
+                    async move {
+                        match request.uri().path().strip_prefix("/v1/") {
+                            Some(name) => Ok(fake.get_node_pool_rest(name, &request)),
+                            None => fake.call(request).await,
+                        }
+                    }
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener).unwrap().serve(make_service);
+        tokio::spawn(async move { server.await.unwrap() });
+        endpoint
+    }
+
+    fn receive(
+        &self,
+        method: &str,
+        name: &str,
+        authorization: Option<String>,
+    ) -> Result<(), Status> {
+        let mut state = self.state.lock().unwrap();
+        state.requests.push(FakeRequest {
+            method: format!("{}", method),
+            name: format!("{}", name),
+            authorization,
+        });
+        match state
+            .failures
+            .get_mut(name)
+            .and_then(|codes| codes.pop_front())
+        {
+            Some(code) => Err(Status::new(code, format!("scripted failure of {}", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn get_cluster(
+        &self,
+        request: Request<GetClusterRequest>,
+    ) -> Result<Response<Cluster>, Status> {
+        let authorization = authorization(&request);
+        let name = request.into_inner().name;
+        self.receive("GetCluster", &name, authorization)?;
+        match self.state.lock().unwrap().clusters.get(&name) {
+            Some(cluster) => Ok(Response::new(cluster.clone())),
+            None => Err(Status::not_found(format!("{} not found", name))),
+        }
+    }
+
+    // parent "projects/P/locations/-" lists clusters in all locations.
+    fn list_clusters(
+        &self,
+        request: Request<ListClustersRequest>,
+    ) -> Result<Response<ListClustersResponse>, Status> {
+        let authorization = authorization(&request);
+        let parent = request.into_inner().parent;
+        self.receive("ListClusters", &parent, authorization)?;
+        let (project, location) = parent
+            .rsplit_once("/locations/")
+            .ok_or_else(|| Status::invalid_argument(format!("invalid parent: {}", parent)))?;
+        let clusters = self
+            .state
+            .lock()
+            .unwrap()
+            .clusters
+            .iter()
+            .filter(|(name, cluster)| {
+                name.starts_with(&format!("{}/locations/", project))
+                    && (location == "-" || cluster.location == location)
+            })
+            .map(|(_, cluster)| cluster.clone())
+            .collect();
+        Ok(Response::new(ListClustersResponse {
+            clusters,
+            missing_zones: vec![],
+        }))
+    }
+
+    fn list_node_pools(
+        &self,
+        request: Request<ListNodePoolsRequest>,
+    ) -> Result<Response<ListNodePoolsResponse>, Status> {
+        let authorization = authorization(&request);
+        let parent = request.into_inner().parent;
+        self.receive("ListNodePools", &parent, authorization)?;
+        let prefix = format!("{}/nodePools/", parent);
+        let node_pools = self
+            .state
+            .lock()
+            .unwrap()
+            .node_pools
+            .iter()
+            .filter(|(name, _)| name.starts_with(&prefix))
+            .map(|(_, node_pool)| node_pool.clone())
+            .collect();
+        Ok(Response::new(ListNodePoolsResponse { node_pools }))
+    }
+
+    fn get_node_pool(
+        &self,
+        request: Request<GetNodePoolRequest>,
+    ) -> Result<Response<NodePool>, Status> {
+        let authorization = authorization(&request);
+        let name = request.into_inner().name;
+        self.receive("GetNodePool", &name, authorization)?;
+        match self.state.lock().unwrap().node_pools.get(&name) {
+            Some(node_pool) => Ok(Response::new(node_pool.clone())),
+            None => Err(Status::not_found(format!("{} not found", name))),
+        }
+    }
+}
+
+impl FakeClusterManager {
+    // get_node_pool_rest answers GET of node pool of GKE REST API with its detail.
+    fn get_node_pool_rest<B>(
+        &self,
+        name: &str,
+        request: &http::Request<B>,
+    ) -> http::Response<BoxBody> {
+        let authorization = request
+            .headers()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .map(|value| format!("{}", value));
+        let result = self.receive("GET", name, authorization).and_then(|()| {
+            let state = self.state.lock().unwrap();
+            match state.node_pools.get(name) {
+                Some(_) => Ok(state
+                    .node_pool_details
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({}))),
+                None => Err(Status::not_found(format!("{} not found", name))),
+            }
+        });
+        let (status, body) = match result {
+            Ok(detail) => (200, detail),
+            Err(status) => (
+                rest_status(status.code()),
+                serde_json::json!({"error": {"code": rest_status(status.code()), "message": status.message()}}),
+            ),
+        };
+        http::Response::builder()
+            .status(status)
+            .header("content-type", "application/json")
+            .body(
+                hyper::Body::from(format!("{}", body))
+                    .map_err(|e| Status::internal(format!("{}", e)))
+                    .boxed_unsync(),
+            )
+            .unwrap()
+    }
+}
+
+fn rest_status(code: Code) -> u16 {
+    match code {
+        Code::NotFound => 404,
+        Code::PermissionDenied => 403,
+        Code::ResourceExhausted => 429,
+        Code::DeadlineExceeded => 504,
+        _ => 503,
+    }
+}
+
+pub fn cluster_name(project: &str, location: &str, cluster: &str) -> String {
+    format!(
+        "projects/{}/locations/{}/clusters/{}",
+        project, location, cluster
+    )
+}
+
+pub fn node_pool_name(project: &str, location: &str, cluster: &str, node_pool: &str) -> String {
+    format!(
+        "{}/nodePools/{}",
+        cluster_name(project, location, cluster),
+        node_pool
+    )
+}
+
+fn authorization<T>(request: &Request<T>) -> Option<String> {
+    request
+        .metadata()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| format!("{}", value))
+}
+
+// Unary adapts a method of FakeClusterManager to tonic::server::UnaryService.
+struct Unary<F>(Option<F>);
+
+impl<F, Req, Resp> Service<Request<Req>> for Unary<F>
+where
+    F: FnOnce(Request<Req>) -> Result<Response<Resp>, Status>,
+{
+    type Response = Response<Resp>;
+    type Error = Status;
+    type Future = Ready<Result<Response<Resp>, Status>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        ready((self.0.take().expect("unary method is called once"))(
+            request,
+        ))
+    }
+}
+
+impl<B> Service<http::Request<B>> for FakeClusterManager
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Never;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let fake = self.clone();
+        match request.uri().path() {
+            "/google.container.v1.ClusterManager/GetCluster" => Box::pin(async move {
+                let method = Unary(Some(|request| fake.get_cluster(request)));
+                let mut grpc = Grpc::new(ProstCodec::<Cluster, GetClusterRequest>::default());
+                Ok(grpc.unary(method, request).await)
+            }),
+            "/google.container.v1.ClusterManager/GetNodePool" => Box::pin(async move {
+                let method = Unary(Some(|request| fake.get_node_pool(request)));
+                let mut grpc = Grpc::new(ProstCodec::<NodePool, GetNodePoolRequest>::default());
+                Ok(grpc.unary(method, request).await)
+            }),
+            "/google.container.v1.ClusterManager/ListClusters" => Box::pin(async move {
+                let method = Unary(Some(|request| fake.list_clusters(request)));
+                let mut grpc =
+                    Grpc::new(ProstCodec::<ListClustersResponse, ListClustersRequest>::default());
+                Ok(grpc.unary(method, request).await)
+            }),
+            "/google.container.v1.ClusterManager/ListNodePools" => Box::pin(async move {
+                let method = Unary(Some(|request| fake.list_node_pools(request)));
+                let mut grpc =
+                    Grpc::new(ProstCodec::<ListNodePoolsResponse, ListNodePoolsRequest>::default());
+                Ok(grpc.unary(method, request).await)
+            }),
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header("grpc-status", (Code::Unimplemented as i32).to_string())
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .unwrap())
+            }),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use crate::support::fake_cluster_manager::FakeRequest;
+
+#[derive(Default)]
+struct State {
+    instance_groups: BTreeMap<String, i32>,
+    requests: Vec<FakeRequest>,
+}
+
+// FakeCompute is an in-process Compute Engine REST server which serves sizes of scripted
+// instance groups. Only GET of instance group is implemented.
+#[derive(Default, Clone)]
+pub struct FakeCompute {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeCompute {
+    pub fn new() -> FakeCompute {
+        FakeCompute::default()
+    }
+
+    pub fn instance_group(self, project: &str, zone: &str, name: &str, size: i32) -> FakeCompute {
+        let path = format!(
+            "/compute/v1/projects/{}/zones/{}/instanceGroups/{}",
+            project, zone, name
+        );
+        self.state
+            .lock()
+            .unwrap()
+            .instance_groups
+            .insert(path, size);
+        self
+    }
+
+    pub fn requests(&self) -> Vec<FakeRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    // start serves on a random local port and returns the endpoint url up to the version.
+    pub async fn start(&self) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/compute/v1", listener.local_addr().unwrap());
+        let fake = self.clone();
+        let make_service = make_service_fn(move |_| {
+            let fake = fake.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let response = fake.receive(request);
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener).unwrap().serve(make_service);
+        tokio::spawn(async move { server.await.unwrap() });
+        endpoint
+    }
+
+    fn receive(&self, request: Request<Body>) -> Response<Body> {
+        let mut state = self.state.lock().unwrap();
+        let path = format!("{}", request.uri().path());
+        state.requests.push(FakeRequest {
+            method: format!("{}", request.method()),
+            name: path.clone(),
+            authorization: request
+                .headers()
+                .get("authorization")
+                .map(|value| format!("{}", value.to_str().unwrap())),
+        });
+
+        match state.instance_groups.get(&path) {
+            Some(size) => Response::new(Body::from(format!(r#"{{"size": {}}}"#, size))),
+            None => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::from(format!(
+                    r#"{{"error": {{"code": 404, "message": "The resource '{}' was not found"}}}}"#,
+                    path
+                )))
+                .unwrap(),
+        }
+    }
+}
+
+// instance_group_url is the url of instance group manager returned in node pool by GKE API.
+pub fn instance_group_url(project: &str, zone: &str, name: &str) -> String {
+    format!(
+        "https://www.googleapis.com/compute/v1/projects/{}/zones/{}/instanceGroupManagers/{}",
+        project, zone, name
+    )
+}
@@ -0,0 +1,49 @@
+#![allow(dead_code)]
+
+pub mod fake_cluster_manager;
+pub mod fake_compute;
+
+use std::io::Write;
+use std::process::Command;
+use tempfile::NamedTempFile;
+
+pub struct YubiOutput {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl YubiOutput {
+    pub fn report(&self) -> serde_yaml::Value {
+        serde_yaml::from_str(&self.stdout).unwrap()
+    }
+}
+
+// write_specfile writes specfile into temporary file, which is removed when it is dropped.
+pub fn write_specfile(specfile: &str) -> NamedTempFile {
+    let mut file = tempfile::Builder::new()
+        .prefix("yubi-test-")
+        .suffix(".yml")
+        .tempfile()
+        .unwrap();
+    file.write_all(specfile.as_bytes()).unwrap();
+    file
+}
+
+// run_yubi runs yubi binary with args, it does not block the runtime serving fake servers.
+pub async fn run_yubi(args: Vec<String>) -> YubiOutput {
+    tokio::task::spawn_blocking(move || {
+        let output = Command::new(env!("CARGO_BIN_EXE_yubi"))
+            .args(&args)
+            .env_remove("GOOGLE_APPLICATION_CREDENTIALS")
+            .output()
+            .unwrap();
+        YubiOutput {
+            code: output.status.code(),
+            stdout: String::from_utf8(output.stdout).unwrap(),
+            stderr: String::from_utf8(output.stderr).unwrap(),
+        }
+    })
+    .await
+    .unwrap()
+}